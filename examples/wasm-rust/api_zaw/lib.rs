@@ -73,14 +73,31 @@ pub extern "C" fn transferOutFloat64Array() -> i32 {
 
 #[no_mangle]
 pub extern "C" fn multiply4x4Float32() -> i32 {
-    let input = interop::get_input();
-    let output = interop::get_output();
+    fn inner() -> Result<(), Error> {
+        let input = interop::get_input();
+        let output = interop::get_output();
 
-    let a_matrices = input.read_array_f32();
-    let b_matrices = input.read_array_f32();
-    let mut result_matrices = output.init_array_f32(a_matrices.len() as u32);
+        let a_matrices = input.read_array_f32();
+        let b_matrices = input.read_array_f32();
 
-    shared::multiply_4x4_f32(&a_matrices, &b_matrices, &mut result_matrices);
+        zaw::zaw_ensure!(
+            a_matrices.len() == b_matrices.len(),
+            "Expected the same number of values in both arrays, found {} and {}",
+            a_matrices.len(),
+            b_matrices.len()
+        );
+        zaw::zaw_ensure!(
+            a_matrices.len() % 16 == 0,
+            "Expected whole 4x4 matrices, found {} values",
+            a_matrices.len()
+        );
 
-    OK
+        let mut result_matrices = output.init_array_f32(a_matrices.len() as u32);
+
+        shared::multiply_4x4_f32(&a_matrices, &b_matrices, &mut result_matrices);
+
+        Ok(())
+    }
+
+    interop::error::handle(inner)
 }
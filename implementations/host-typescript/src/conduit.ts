@@ -1,3 +1,50 @@
+// The bytes "ZAWM" that start a message header
+export const HEADER_MAGIC = 0x4d57415a
+export const PROTOCOL_VERSION = 1
+export const HEADER_SIZE = 16
+// Header flag set when the payload is type tagged
+export const FLAG_TYPE_TAGS = 1
+
+// The type in the low 5 bits of a type tag
+export const TypeTag = {
+  Uint8: 1,
+  Int8: 2,
+  Uint16: 3,
+  Uint32: 4,
+  Int32: 5,
+  Int64: 6,
+  Float16: 7,
+  BFloat16: 8,
+  Float32: 9,
+  Float64: 10,
+  Date32: 11,
+  TimestampMillis: 12,
+  Duration: 13,
+} as const
+
+// Set on a type tag that precedes a run of elements rather than a single value
+export const TAG_ELEMENTS = 0x20
+
+const TYPE_TAG_NAMES = Object.keys(TypeTag)
+
+// Describes a raw tag byte for error messages, e.g. "Float64 value"
+function describeTag(tag: number): string {
+  const name = TYPE_TAG_NAMES[(tag & 0x1f) - 1]
+
+  if (name === undefined || tag >>> 6 !== 0) {
+    return `unknown tag 0x${tag.toString(16).padStart(2, '0')}`
+  }
+
+  return `${name} ${tag & TAG_ELEMENTS ? 'elements' : 'value'}`
+}
+
+export type MessageHeader = {
+  version: number
+  flags: number
+  schemaId: number
+  payloadLength: number
+}
+
 function alignUp(x: number, bytes: 4 | 8): number {
   const mask = bytes - 1
 
@@ -6,6 +53,10 @@ function alignUp(x: number, bytes: 4 | 8): number {
 
 class Channel {
   private offset = 0
+  // Reads stop here once a message header gives the payload length
+  protected limit: number
+  // Set by a message header whose flags say the payload is type tagged
+  protected typeTags = false
   storageUint8: Uint8Array
   storageUint32: Uint32Array
   storageInt32: Int32Array
@@ -18,10 +69,17 @@ class Channel {
     this.storageInt32 = new Int32Array(buffer, offset, sizeInBytes / Int32Array.BYTES_PER_ELEMENT)
     this.storageFloat32 = new Float32Array(buffer, offset, sizeInBytes / Float32Array.BYTES_PER_ELEMENT)
     this.storageFloat64 = new Float64Array(buffer, offset, sizeInBytes / Float64Array.BYTES_PER_ELEMENT)
+    this.limit = this.storageUint8.length
   }
 
   reset(): void {
     this.offset = 0
+    this.limit = this.storageUint8.length
+    this.typeTags = false
+  }
+
+  getLength(): number {
+    return this.offset
   }
 
   offset8(): number {
@@ -47,7 +105,7 @@ class Channel {
   advance8(count: number): void {
     this.offset += count
 
-    if (this.offset > this.storageUint8.length) {
+    if (this.offset > this.limit) {
       throw Error('Reached end of channel')
     }
   }
@@ -62,32 +120,71 @@ class Channel {
 }
 
 export class Writer extends Channel {
+  // Writes a message header at the start of the channel; call endMessage once the payload is written.
+  // With typeTags, every value that follows is prefixed with its type tag, as modules built with them expect
+  beginMessage(schemaId: number, typeTags = false): void {
+    if (this.getLength() !== 0) {
+      throw Error('Message header must be written at the start of the channel')
+    }
+
+    // The header itself is never tagged
+    this.typeTags = false
+    this.writeUint32(HEADER_MAGIC)
+    // Version and flags are both Uint16s
+    this.writeUint32(PROTOCOL_VERSION | ((typeTags ? FLAG_TYPE_TAGS : 0) << 16))
+    this.writeUint32(schemaId)
+    this.writeUint32(0)
+    this.typeTags = typeTags
+  }
+
+  private writeTag(type: number, elements = false): void {
+    if (this.typeTags) {
+      this.storageUint8[this.offset8()] = elements ? type | TAG_ELEMENTS : type
+      this.advance8(1)
+    }
+  }
+
+  // Writes the payload length into the message header, returning it
+  endMessage(): number {
+    const payloadLength = this.getLength() - HEADER_SIZE
+
+    this.storageUint32[3] = payloadLength
+
+    return payloadLength
+  }
+
   writeUint8(value: number): void {
+    this.writeTag(TypeTag.Uint8)
     this.storageUint8[this.offset8()] = value
     this.advance8(1)
   }
 
   writeUint32(value: number): void {
+    this.writeTag(TypeTag.Uint32)
     this.storageUint32[this.offset32()] = value
     this.advance32(1)
   }
 
   writeInt32(value: number): void {
+    this.writeTag(TypeTag.Int32)
     this.storageInt32[this.offset32()] = value
     this.advance32(1)
   }
 
   writeFloat32(value: number): void {
+    this.writeTag(TypeTag.Float32)
     this.storageFloat32[this.offset32()] = value
     this.advance32(1)
   }
 
   writeFloat64(value: number): void {
+    this.writeTag(TypeTag.Float64)
     this.storageFloat64[this.offset64()] = value
     this.advance64(1)
   }
 
   initUint8(): (value: number) => void {
+    this.writeTag(TypeTag.Uint8)
     const offset = this.offset8()
 
     this.advance8(1)
@@ -98,6 +195,7 @@ export class Writer extends Channel {
   }
 
   initUint32(): (value: number) => void {
+    this.writeTag(TypeTag.Uint32)
     const offset = this.offset32()
 
     this.advance32(1)
@@ -108,6 +206,7 @@ export class Writer extends Channel {
   }
 
   initInt32(): (value: number) => void {
+    this.writeTag(TypeTag.Int32)
     const offset = this.offset32()
 
     this.advance32(1)
@@ -118,6 +217,7 @@ export class Writer extends Channel {
   }
 
   initFloat32(): (value: number) => void {
+    this.writeTag(TypeTag.Float32)
     const offset = this.offset32()
 
     this.advance32(1)
@@ -128,6 +228,7 @@ export class Writer extends Channel {
   }
 
   initFloat64(): (value: number) => void {
+    this.writeTag(TypeTag.Float64)
     const offset = this.offset64()
 
     this.advance64(1)
@@ -138,6 +239,7 @@ export class Writer extends Channel {
   }
 
   initUint8Elements(length: number): Uint8Array {
+    this.writeTag(TypeTag.Uint8, true)
     const start = this.offset8()
 
     this.advance8(length)
@@ -151,6 +253,7 @@ export class Writer extends Channel {
   }
 
   initUint32Elements(length: number): Uint32Array {
+    this.writeTag(TypeTag.Uint32, true)
     const start = this.offset32()
 
     this.advance32(length)
@@ -164,6 +267,7 @@ export class Writer extends Channel {
   }
 
   initInt32Elements(length: number): Int32Array {
+    this.writeTag(TypeTag.Int32, true)
     const start = this.offset32()
 
     this.advance32(length)
@@ -177,6 +281,7 @@ export class Writer extends Channel {
   }
 
   initFloat32Elements(length: number): Float32Array {
+    this.writeTag(TypeTag.Float32, true)
     const start = this.offset32()
 
     this.advance32(length)
@@ -190,6 +295,7 @@ export class Writer extends Channel {
   }
 
   initFloat64Elements(length: number): Float64Array {
+    this.writeTag(TypeTag.Float64, true)
     const start = this.offset64()
 
     this.advance64(length)
@@ -203,26 +309,31 @@ export class Writer extends Channel {
   }
 
   copyUint8Elements(arr: Uint8Array | number[]): void {
+    this.writeTag(TypeTag.Uint8, true)
     this.storageUint8.set(arr, this.offset8())
     this.advance8(arr.length)
   }
 
   copyUint32Elements(arr: Uint32Array | number[]): void {
+    this.writeTag(TypeTag.Uint32, true)
     this.storageUint32.set(arr, this.offset32())
     this.advance32(arr.length)
   }
 
   copyInt32Elements(arr: Int32Array | number[]): void {
+    this.writeTag(TypeTag.Int32, true)
     this.storageInt32.set(arr, this.offset32())
     this.advance32(arr.length)
   }
 
   copyFloat32Elements(arr: Float32Array | number[]): void {
+    this.writeTag(TypeTag.Float32, true)
     this.storageFloat32.set(arr, this.offset32())
     this.advance32(arr.length)
   }
 
   copyFloat64Elements(arr: Float64Array | number[]): void {
+    this.writeTag(TypeTag.Float64, true)
     this.storageFloat64.set(arr, this.offset64())
     this.advance64(arr.length)
   }
@@ -269,7 +380,64 @@ export class Writer extends Channel {
 }
 
 export class Reader extends Channel {
+  // Reads and validates a message header, then bounds the reader to the payload and
+  // checks type tags if its flags say the payload has them
+  readMessageHeader(schemaId: number): MessageHeader {
+    // The header itself is never tagged
+    this.typeTags = false
+
+    const magic = this.readUint32()
+
+    if (magic !== HEADER_MAGIC) {
+      throw Error(`Invalid message header: bad magic 0x${magic.toString(16).padStart(8, '0')}`)
+    }
+
+    const versionAndFlags = this.readUint32()
+    const header = {
+      version: versionAndFlags & 0xffff,
+      flags: versionAndFlags >>> 16,
+      schemaId: this.readUint32(),
+      payloadLength: this.readUint32(),
+    }
+
+    if (header.version !== PROTOCOL_VERSION) {
+      throw Error(`Protocol version mismatch: expected ${PROTOCOL_VERSION} but found ${header.version}`)
+    }
+
+    if (header.schemaId !== schemaId) {
+      throw Error(`Schema mismatch: expected ${schemaId} but found ${header.schemaId}`)
+    }
+
+    const capacity = this.storageUint8.length - HEADER_SIZE
+
+    if (header.payloadLength > capacity) {
+      throw Error(`Payload length ${header.payloadLength} exceeds channel capacity ${capacity}`)
+    }
+
+    this.limit = HEADER_SIZE + header.payloadLength
+    this.typeTags = (header.flags & FLAG_TYPE_TAGS) !== 0
+
+    return header
+  }
+
+  private checkTag(type: number, elements = false): void {
+    if (!this.typeTags) {
+      return
+    }
+
+    const offset = this.offset8()
+    const expected = elements ? type | TAG_ELEMENTS : type
+    const found = this.storageUint8[offset]
+
+    this.advance8(1)
+
+    if (found !== expected) {
+      throw Error(`Type tag mismatch at offset ${offset}: expected ${describeTag(expected)} but found ${describeTag(found)}`)
+    }
+  }
+
   readUint8(): number {
+    this.checkTag(TypeTag.Uint8)
     const result = this.storageUint8[this.offset8()]
 
     this.advance8(1)
@@ -278,6 +446,7 @@ export class Reader extends Channel {
   }
 
   readUint32(): number {
+    this.checkTag(TypeTag.Uint32)
     const result = this.storageUint32[this.offset32()]
 
     this.advance32(1)
@@ -286,6 +455,7 @@ export class Reader extends Channel {
   }
 
   readInt32(): number {
+    this.checkTag(TypeTag.Int32)
     const result = this.storageInt32[this.offset32()]
 
     this.advance32(1)
@@ -294,6 +464,7 @@ export class Reader extends Channel {
   }
 
   readFloat32(): number {
+    this.checkTag(TypeTag.Float32)
     const result = this.storageFloat32[this.offset32()]
 
     this.advance32(1)
@@ -302,6 +473,7 @@ export class Reader extends Channel {
   }
 
   readFloat64(): number {
+    this.checkTag(TypeTag.Float64)
     const result = this.storageFloat64[this.offset64()]
 
     this.advance64(1)
@@ -310,6 +482,7 @@ export class Reader extends Channel {
   }
 
   readUint8Elements(length: number): Uint8Array {
+    this.checkTag(TypeTag.Uint8, true)
     const start = this.offset8()
     const view = this.storageUint8.subarray(start, start + length)
 
@@ -319,6 +492,7 @@ export class Reader extends Channel {
   }
 
   readUint32Elements(length: number): Uint32Array {
+    this.checkTag(TypeTag.Uint32, true)
     const start = this.offset32()
     const view = this.storageUint32.subarray(start, start + length)
 
@@ -328,6 +502,7 @@ export class Reader extends Channel {
   }
 
   readInt32Elements(length: number): Int32Array {
+    this.checkTag(TypeTag.Int32, true)
     const start = this.offset32()
     const view = this.storageInt32.subarray(start, start + length)
 
@@ -337,6 +512,7 @@ export class Reader extends Channel {
   }
 
   readFloat32Elements(length: number): Float32Array {
+    this.checkTag(TypeTag.Float32, true)
     const start = this.offset32()
     const view = this.storageFloat32.subarray(start, start + length)
 
@@ -346,6 +522,7 @@ export class Reader extends Channel {
   }
 
   readFloat64Elements(length: number): Float64Array {
+    this.checkTag(TypeTag.Float64, true)
     const start = this.offset64()
     const view = this.storageFloat64.subarray(start, start + length)
 
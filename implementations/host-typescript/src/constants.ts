@@ -2,3 +2,27 @@ export const MAX_LOG_SIZE = 1024
 export const MAX_ERROR_SIZE = 256
 export const PAGE_SIZE = 65536
 export const DEFAULT_INITIAL_PAGES = 17
+
+export const ErrorCode = {
+  UNSPECIFIED: 0,
+  INVALID_INPUT: 1,
+  CAPACITY_EXCEEDED: 2,
+  INTERNAL: 3,
+  PANIC: 4,
+} as const
+
+// Set on a level when the message continues in the next hostLog call or record
+export const LOG_CONTINUED = 0x80
+// The hostLog level of a batch of buffered records
+export const LOG_BATCH = 0xff
+// Each buffered record starts with its level (u8), sequence number (u32) and length (u32)
+export const LOG_RECORD_HEADER_SIZE = 9
+
+export const LogLevel = {
+  OFF: 0,
+  ERROR: 1,
+  WARN: 2,
+  INFO: 3,
+  DEBUG: 4,
+  TRACE: 5,
+} as const
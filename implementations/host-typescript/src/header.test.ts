@@ -0,0 +1,57 @@
+import { describe, test, expect } from 'vitest'
+import { HEADER_SIZE, Reader, Writer } from './conduit'
+
+describe('Message Header Tests', () => {
+  test('Round trips a header and payload', () => {
+    const buffer = new ArrayBuffer(64)
+    const writer = new Writer(buffer)
+    const reader = new Reader(buffer)
+
+    writer.beginMessage(7)
+    writer.writeUint32(42)
+
+    expect(writer.endMessage()).toEqual(4)
+
+    const header = reader.readMessageHeader(7)
+
+    expect(header).toEqual({ version: 1, flags: 0, schemaId: 7, payloadLength: 4 })
+    expect(reader.readUint32()).toEqual(42)
+  })
+
+  test('Stops reading at the end of the payload', () => {
+    const buffer = new ArrayBuffer(64)
+    const writer = new Writer(buffer)
+    const reader = new Reader(buffer)
+
+    writer.beginMessage(7)
+    writer.writeUint32(42)
+    writer.endMessage()
+
+    reader.readMessageHeader(7)
+    reader.readUint32()
+
+    expect(() => reader.readUint32()).toThrow('Reached end of channel')
+
+    reader.reset()
+    reader.advance8(HEADER_SIZE + 4)
+    expect(reader.readUint32()).toEqual(0)
+  })
+
+  test('Rejects a mismatched schema id', () => {
+    const buffer = new ArrayBuffer(64)
+    const writer = new Writer(buffer)
+
+    writer.beginMessage(7)
+    writer.endMessage()
+
+    expect(() => new Reader(buffer).readMessageHeader(8)).toThrow('Schema mismatch: expected 8 but found 7')
+  })
+
+  test('Rejects a channel without a header', () => {
+    const buffer = new ArrayBuffer(64)
+
+    new Writer(buffer).writeUint32(42)
+
+    expect(() => new Reader(buffer).readMessageHeader(7)).toThrow('Invalid message header: bad magic 0x0000002a')
+  })
+})
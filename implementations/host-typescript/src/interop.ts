@@ -1,5 +1,14 @@
 import { Reader, Writer } from './conduit'
-import { DEFAULT_INITIAL_PAGES, MAX_ERROR_SIZE, MAX_LOG_SIZE } from './constants'
+import {
+  DEFAULT_INITIAL_PAGES,
+  ErrorCode,
+  LOG_BATCH,
+  LOG_CONTINUED,
+  LOG_RECORD_HEADER_SIZE,
+  LogLevel,
+  MAX_ERROR_SIZE,
+  MAX_LOG_SIZE,
+} from './constants'
 import { generateBinding } from './binding'
 import { ZawReturn } from './types'
 
@@ -7,7 +16,11 @@ export type InstanceOptions = {
   inputChannelSize: number
   outputChannelSize: number
   initialMemoryPages?: number
-  log?: (message: string) => void
+  // Receives each message with its level; modules that don't report a level log at INFO.
+  // Buffered records also carry the sequence number the module assigned them
+  log?: (message: string, level: number, sequence?: number) => void
+  // Passed to the module's setLogLevel export, if it has one
+  logLevel?: number
 }
 
 export type ExportBase = Record<string, () => number> & {
@@ -15,6 +28,43 @@ export type ExportBase = Record<string, () => number> & {
   getErrorPtr: () => number
   allocateInputChannel: (sizeInBytes: number) => number
   allocateOutputChannel: (sizeInBytes: number) => number
+  setInputLength?: (length: number) => number
+  setLogLevel?: (level: number) => number
+  getOutputLength?: () => number
+  getErrorSize?: () => number
+  getErrorCode?: () => number
+  getLogSize?: () => number
+}
+
+export { ErrorCode, LogLevel }
+
+const defaultLog = (message: string, level: number): void => {
+  switch (level) {
+    case LogLevel.ERROR:
+      console.error(message)
+      break
+    case LogLevel.WARN:
+      console.warn(message)
+      break
+    case LogLevel.DEBUG:
+    case LogLevel.TRACE:
+      console.debug(message)
+      break
+    default:
+      console.log(message)
+  }
+}
+
+// An error raised by the module, with its category and any key-value fields
+export class ZawError extends Error {
+  constructor(
+    message: string,
+    public readonly code: number,
+    public readonly fields: Record<string, string>,
+  ) {
+    super(message)
+    this.name = 'ZawError'
+  }
 }
 
 export type BindingFactory = <Args extends unknown[], Result>(
@@ -30,6 +80,7 @@ export type Instance<T extends Record<string, unknown>> = {
   createView: <T>(init: (buffer: ArrayBuffer) => T) => () => T
   getInput: () => Writer
   getOutput: () => Reader
+  getOutputLength: () => number
   handleError: (func: () => number) => void
   getSize: () => number
   bind: BindingFactory
@@ -39,15 +90,15 @@ export async function createInstance<T extends Record<string, unknown>>(
   wasmBuffer: BufferSource | ArrayBuffer,
   options: InstanceOptions,
 ): Promise<Instance<T>> {
-  const { inputChannelSize, outputChannelSize, initialMemoryPages = DEFAULT_INITIAL_PAGES, log = console.log.bind(console) } = options
+  const { inputChannelSize, outputChannelSize, initialMemoryPages = DEFAULT_INITIAL_PAGES, log = defaultLog, logLevel } = options
   const memory = new WebAssembly.Memory({ initial: initialMemoryPages })
   const textDecoder = new TextDecoder('utf-8')
 
   const imports = {
     env: {
       memory,
-      hostLog: () => {
-        hostLog() // has to be hoisted
+      hostLog: (level?: number) => {
+        hostLog(level ?? LogLevel.INFO) // has to be hoisted
       },
     },
   }
@@ -72,35 +123,90 @@ export async function createInstance<T extends Record<string, unknown>>(
 
   const logPtr = exports.getLogPtr()
   const errPtr = exports.getErrorPtr()
-  const inputPtr = exports.allocateInputChannel(inputChannelSize)
-  const outputPtr = exports.allocateOutputChannel(outputChannelSize)
+  const logSize = exports.getLogSize?.() ?? MAX_LOG_SIZE
+  const errSize = exports.getErrorSize?.() ?? MAX_ERROR_SIZE
 
   const getBytes = createView(buffer => new Uint8ClampedArray(buffer))
-  const getLogData = createView(buffer => new Uint8ClampedArray(buffer, logPtr, MAX_LOG_SIZE))
-  const getErrorData = createView(buffer => new Uint8ClampedArray(buffer, errPtr, MAX_ERROR_SIZE))
-  const getInputChannel = createView(buffer => new Writer(buffer, inputPtr, inputChannelSize))
-  const getOutputChannel = createView(buffer => new Reader(buffer, outputPtr, outputChannelSize))
+  const getLogData = createView(buffer => new Uint8ClampedArray(buffer, logPtr, logSize))
+  const getErrorData = createView(buffer => new Uint8ClampedArray(buffer, errPtr, errSize))
+
+  // Long messages arrive in chunks, each flagged as continued except the last
+  let pendingLog = ''
+
+  const receiveLog = (message: string, level: number, sequence?: number): void => {
+    if (level & LOG_CONTINUED) {
+      pendingLog += message
+      return
+    }
 
-  const hostLog = (): void => {
+    log(pendingLog + message, level, sequence)
+    pendingLog = ''
+  }
+
+  // Buffered records are laid out back to back, ending with a zero level
+  const readLogBatch = (data: Uint8ClampedArray): void => {
+    const view = new DataView(data.buffer, data.byteOffset, data.byteLength)
+    let pos = 0
+
+    while (pos + LOG_RECORD_HEADER_SIZE <= data.length && data[pos] !== 0) {
+      const level = data[pos]
+      const sequence = view.getUint32(pos + 1, true)
+      const length = view.getUint32(pos + 5, true)
+      const start = pos + LOG_RECORD_HEADER_SIZE
+
+      receiveLog(textDecoder.decode(data.subarray(start, start + length)), level, sequence)
+      pos = start + length
+    }
+  }
+
+  const hostLog = (level: number): void => {
     const data = getLogData()
+
+    if (level === LOG_BATCH) {
+      readLogBatch(data)
+      return
+    }
+
     const length = data.indexOf(0)
     const message = textDecoder.decode(data.subarray(0, length))
 
-    log(message)
+    receiveLog(message, level)
   }
 
-  const throwWasmError = (e?: Error): void => {
+  // Fields follow the message as null-terminated `key=value` entries, ending with an empty entry
+  const readErrorFields = (data: Uint8ClampedArray, start: number): Record<string, string> => {
+    const fields: Record<string, string> = {}
+    let pos = start
+
+    while (pos < data.length && data[pos] !== 0) {
+      const end = data.indexOf(0, pos)
+      const field = textDecoder.decode(data.subarray(pos, end === -1 ? data.length : end))
+      const split = field.indexOf('=')
+
+      if (split > 0) {
+        fields[field.slice(0, split)] = field.slice(split + 1)
+      }
+
+      if (end === -1) break
+      pos = end + 1
+    }
+
+    return fields
+  }
+
+  const throwWasmError = (fallbackCode: number, e?: Error): void => {
     const data = getErrorData()
     const length = data.indexOf(0)
+    const code = exports.getErrorCode?.() ?? fallbackCode
 
     if (length > 0) {
       const message = textDecoder.decode(data.subarray(0, length))
 
-      throw Error(message)
+      throw new ZawError(message, code, readErrorFields(data, length + 1))
     } else if (e !== undefined) {
       throw e
     } else {
-      throw Error('Unknown error')
+      throw new ZawError('Unknown error', code, {})
     }
   }
 
@@ -110,12 +216,35 @@ export async function createInstance<T extends Record<string, unknown>>(
     try {
       result = func()
     } catch (e) {
-      throwWasmError(e as Error)
+      // A trap, most likely from a panic
+      throwWasmError(ErrorCode.PANIC, e as Error)
     }
 
     if (result !== 0) {
-      throwWasmError()
+      throwWasmError(result === 2 ? ErrorCode.PANIC : ErrorCode.UNSPECIFIED)
+    }
+  }
+
+  // A failed allocation returns 0 and describes why in the error region
+  const allocateChannel = (allocate: (size: number) => number, size: number): number => {
+    const ptr = allocate(size)
+
+    if (ptr === 0) {
+      throwWasmError(ErrorCode.CAPACITY_EXCEEDED)
     }
+
+    return ptr
+  }
+
+  const inputPtr = allocateChannel(exports.allocateInputChannel, inputChannelSize)
+  const outputPtr = allocateChannel(exports.allocateOutputChannel, outputChannelSize)
+  const getInputChannel = createView(buffer => new Writer(buffer, inputPtr, inputChannelSize))
+  const getOutputChannel = createView(buffer => new Reader(buffer, outputPtr, outputChannelSize))
+
+  const { setLogLevel } = exports
+
+  if (logLevel !== undefined && setLogLevel !== undefined) {
+    handleError(() => setLogLevel(logLevel))
   }
 
   const getInput = (): Writer => {
@@ -134,11 +263,26 @@ export async function createInstance<T extends Record<string, unknown>>(
     return input
   }
 
+  const { setInputLength } = exports
+
+  // Modules without the export can't report it, so assume the whole channel is meaningful
+  const getOutputLength = (): number => exports.getOutputLength?.() ?? outputChannelSize
+
+  // Report the written input length first so the module can bound its reader
+  const withInputLength = (func: () => ZawReturn): (() => ZawReturn) =>
+    setInputLength === undefined
+      ? func
+      : () => {
+          const result = setInputLength(getInputChannel().getLength())
+
+          return result === 0 ? func() : (result as ZawReturn)
+        }
+
   const bind: BindingFactory = <T extends unknown[], R>(
     func: () => ZawReturn,
     write: (input: Writer, ...args: T) => void,
     read: (output: Reader, ...args: T) => R,
-  ) => generateBinding(func, write, read, getInput, getOutput, handleError)
+  ) => generateBinding(withInputLength(func), write, read, getInput, getOutput, handleError)
 
   return {
     exports,
@@ -148,6 +292,7 @@ export async function createInstance<T extends Record<string, unknown>>(
     getBytes,
     getInput,
     getOutput,
+    getOutputLength,
     handleError,
     bind,
   }
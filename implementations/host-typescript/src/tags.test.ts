@@ -0,0 +1,63 @@
+import { describe, test, expect } from 'vitest'
+import { HEADER_SIZE, Reader, Writer } from './conduit'
+
+describe('Type Tag Tests', () => {
+  test('Prefixes every value with its tag, as the Rust module does', () => {
+    const buffer = new ArrayBuffer(64)
+    const writer = new Writer(buffer)
+
+    writer.beginMessage(3, true)
+    writer.writeUint8(5)
+    writer.writeUint32(7)
+    writer.copyUint8Array([1, 2])
+    writer.endMessage()
+
+    const payload = new Uint8Array(buffer, HEADER_SIZE, 19)
+
+    expect(Array.from(payload)).toEqual([1, 5, 4, 0, 7, 0, 0, 0, 4, 0, 0, 0, 2, 0, 0, 0, 33, 1, 2])
+  })
+
+  test('Checks tags when the header says the payload has them', () => {
+    const buffer = new ArrayBuffer(64)
+    const writer = new Writer(buffer)
+    const reader = new Reader(buffer)
+
+    writer.beginMessage(3, true)
+    writer.writeFloat64(1.5)
+    writer.copyInt32Array([1, 2])
+    writer.endMessage()
+
+    expect(reader.readMessageHeader(3).flags).toEqual(1)
+    expect(reader.readFloat64()).toEqual(1.5)
+    expect(Array.from(reader.readInt32Array())).toEqual([1, 2])
+  })
+
+  test('Reports the offset and both types on a mismatch', () => {
+    const buffer = new ArrayBuffer(64)
+    const writer = new Writer(buffer)
+    const reader = new Reader(buffer)
+
+    writer.beginMessage(3, true)
+    writer.writeFloat64(1.5)
+    writer.endMessage()
+
+    reader.readMessageHeader(3)
+
+    expect(() => reader.readFloat32()).toThrow(
+      'Type tag mismatch at offset 16: expected Float32 value but found Float64 value',
+    )
+  })
+
+  test('Reads untagged payloads as before', () => {
+    const buffer = new ArrayBuffer(64)
+    const writer = new Writer(buffer)
+    const reader = new Reader(buffer)
+
+    writer.beginMessage(3)
+    writer.writeFloat64(1.5)
+    writer.endMessage()
+
+    expect(reader.readMessageHeader(3).flags).toEqual(0)
+    expect(reader.readFloat64()).toEqual(1.5)
+  })
+})
@@ -1 +1 @@
-export type ZawReturn = 0 | 1
+export type ZawReturn = 0 | 1 | 2 // OK, ERROR, PANIC
@@ -0,0 +1,381 @@
+use core::fmt;
+use core::ops::{Add, Mul, Neg, Sub};
+
+use super::{round_f64, Reader, Writer};
+
+/// The largest supported scale; `10^18` is the largest power of ten that fits in an `i64`.
+pub const MAX_SCALE: u32 = 18;
+
+const POW10: [i64; MAX_SCALE as usize + 1] = [
+    1,
+    10,
+    100,
+    1_000,
+    10_000,
+    100_000,
+    1_000_000,
+    10_000_000,
+    100_000_000,
+    1_000_000_000,
+    10_000_000_000,
+    100_000_000_000,
+    1_000_000_000_000,
+    10_000_000_000_000,
+    100_000_000_000_000,
+    1_000_000_000_000_000,
+    10_000_000_000_000_000,
+    100_000_000_000_000_000,
+    1_000_000_000_000_000_000,
+];
+
+fn pow10(scale: u32) -> i64 {
+    assert!(scale <= MAX_SCALE, "Invalid decimal scale: {}", scale);
+    POW10[scale as usize]
+}
+
+/// An exact fixed-point decimal: `mantissa / 10^scale`.
+///
+/// Arithmetic is exact; the `checked_*` methods return `None` on overflow and the
+/// operator impls panic instead. Use this for monetary values that must not drift
+/// the way `f64` sums do.
+///
+/// The fields are private so the scale is always within [`MAX_SCALE`].
+///
+/// # Examples
+///
+/// ```rust
+/// # use zaw::conduit::Decimal;
+/// let price = Decimal::new(1999, 2); // 19.99
+/// let total = price * 3 + Decimal::new(5, 1);
+/// assert_eq!(Decimal::new(6047, 2), total);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Decimal {
+    mantissa: i64,
+    scale: u32,
+}
+
+/// A decimal array read from the channel, sharing a single scale.
+///
+/// The wire encoding is a `Uint32` scale followed by the mantissas as a
+/// length-prefixed `Int64[]` array.
+#[derive(Debug, Clone, Copy)]
+pub struct DecimalArray<'a> {
+    scale: u32,
+    mantissas: &'a [i64],
+}
+
+impl Decimal {
+    /// Creates a decimal with value `mantissa / 10^scale`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `scale` is greater than [`MAX_SCALE`].
+    pub fn new(mantissa: i64, scale: u32) -> Self {
+        pow10(scale);
+        Self { mantissa, scale }
+    }
+
+    /// Creates a decimal with value `mantissa / 10^scale`.
+    ///
+    /// Returns `None` if `scale` is greater than [`MAX_SCALE`].
+    pub fn try_new(mantissa: i64, scale: u32) -> Option<Self> {
+        (scale <= MAX_SCALE).then_some(Self { mantissa, scale })
+    }
+
+    /// Returns the mantissa, the value multiplied by `10^scale`.
+    pub fn mantissa(self) -> i64 {
+        self.mantissa
+    }
+
+    /// Returns the number of decimal places.
+    pub fn scale(self) -> u32 {
+        self.scale
+    }
+
+    /// Converts an `f64` to a decimal with the given scale, rounding half away from zero.
+    ///
+    /// Returns `None` if `value` is not finite or does not fit at this scale.
+    pub fn from_f64(value: f64, scale: u32) -> Option<Self> {
+        let scaled = round_f64(value * pow10(scale) as f64);
+        // i64::MAX is not exactly representable, so compare against 2^63
+        if !scaled.is_finite() || scaled.abs() >= 9_223_372_036_854_775_808.0 {
+            return None;
+        }
+        Some(Self::new(scaled as i64, scale))
+    }
+
+    /// Converts this decimal to the nearest `f64`.
+    pub fn to_f64(self) -> f64 {
+        self.mantissa as f64 / pow10(self.scale) as f64
+    }
+
+    /// Returns the same value at a different scale.
+    ///
+    /// Returns `None` if the mantissa overflows, or if reducing the scale would
+    /// discard non-zero digits.
+    pub fn rescale(self, scale: u32) -> Option<Self> {
+        if scale >= self.scale {
+            let factor = pow10(scale - self.scale);
+            Some(Self::new(self.mantissa.checked_mul(factor)?, scale))
+        } else {
+            let factor = pow10(self.scale - scale);
+            if self.mantissa % factor != 0 {
+                return None;
+            }
+            Some(Self::new(self.mantissa / factor, scale))
+        }
+    }
+
+    /// Adds two decimals exactly, using the larger of the two scales.
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        let scale = self.scale.max(other.scale);
+        let a = self.rescale(scale)?;
+        let b = other.rescale(scale)?;
+        Some(Self::new(a.mantissa.checked_add(b.mantissa)?, scale))
+    }
+
+    /// Subtracts two decimals exactly, using the larger of the two scales.
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        self.checked_add(other.checked_neg()?)
+    }
+
+    /// Negates this decimal.
+    pub fn checked_neg(self) -> Option<Self> {
+        Some(Self::new(self.mantissa.checked_neg()?, self.scale))
+    }
+
+    /// Multiplies this decimal by an integer exactly.
+    pub fn checked_mul_int(self, factor: i64) -> Option<Self> {
+        Some(Self::new(self.mantissa.checked_mul(factor)?, self.scale))
+    }
+
+    /// Converts each `f64` in `src` into a mantissa at `scale` in `dst`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `src` and `dst` have different lengths, or if a value is not
+    /// finite or does not fit at this scale.
+    pub fn encode_slice(src: &[f64], scale: u32, dst: &mut [i64]) {
+        assert_eq!(src.len(), dst.len(), "Slice length mismatch");
+        for (out, &value) in dst.iter_mut().zip(src) {
+            *out = match Self::from_f64(value, scale) {
+                Some(decimal) => decimal.mantissa,
+                None => panic!(
+                    "Value {} does not fit in a decimal of scale {}",
+                    value, scale
+                ),
+            };
+        }
+    }
+}
+
+impl PartialEq for Decimal {
+    /// Compares by value, so `1.50` equals `1.5`.
+    fn eq(&self, other: &Self) -> bool {
+        let scale = self.scale.max(other.scale);
+        let a = self.mantissa as i128 * pow10(scale - self.scale) as i128;
+        let b = other.mantissa as i128 * pow10(scale - other.scale) as i128;
+        a == b
+    }
+}
+
+impl Eq for Decimal {}
+
+impl Add for Decimal {
+    type Output = Decimal;
+
+    fn add(self, rhs: Decimal) -> Decimal {
+        self.checked_add(rhs).expect("Decimal overflow")
+    }
+}
+
+impl Sub for Decimal {
+    type Output = Decimal;
+
+    fn sub(self, rhs: Decimal) -> Decimal {
+        self.checked_sub(rhs).expect("Decimal overflow")
+    }
+}
+
+impl Neg for Decimal {
+    type Output = Decimal;
+
+    fn neg(self) -> Decimal {
+        self.checked_neg().expect("Decimal overflow")
+    }
+}
+
+impl Mul<i64> for Decimal {
+    type Output = Decimal;
+
+    fn mul(self, rhs: i64) -> Decimal {
+        self.checked_mul_int(rhs).expect("Decimal overflow")
+    }
+}
+
+impl fmt::Display for Decimal {
+    /// Formats the exact value, e.g. `-12.30` for mantissa `-1230` at scale 2.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.mantissa < 0 { "-" } else { "" };
+        let magnitude = self.mantissa.unsigned_abs();
+
+        if self.scale == 0 {
+            return write!(f, "{}{}", sign, magnitude);
+        }
+
+        let factor = pow10(self.scale) as u64;
+        write!(
+            f,
+            "{}{}.{:0width$}",
+            sign,
+            magnitude / factor,
+            magnitude % factor,
+            width = self.scale as usize
+        )
+    }
+}
+
+impl<'a> DecimalArray<'a> {
+    /// Returns the number of decimal places shared by every value.
+    pub fn scale(&self) -> u32 {
+        self.scale
+    }
+
+    /// Returns the mantissas, each value multiplied by `10^scale`.
+    pub fn mantissas(&self) -> &'a [i64] {
+        self.mantissas
+    }
+
+    /// Returns the number of values.
+    pub fn len(&self) -> usize {
+        self.mantissas.len()
+    }
+
+    /// Returns `true` if there are no values.
+    pub fn is_empty(&self) -> bool {
+        self.mantissas.is_empty()
+    }
+
+    /// Returns the value at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn get(&self, index: usize) -> Decimal {
+        Decimal::new(self.mantissas[index], self.scale)
+    }
+
+    /// Sums all values exactly, returning `None` if the total overflows.
+    ///
+    /// Intermediate sums are accumulated in 128 bits, so only the final total
+    /// has to fit in an `i64` mantissa.
+    pub fn checked_sum(&self) -> Option<Decimal> {
+        let total: i128 = self.mantissas.iter().map(|&m| m as i128).sum();
+        Some(Decimal::new(i64::try_from(total).ok()?, self.scale))
+    }
+
+    /// Sums all values exactly.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the total overflows.
+    pub fn sum(&self) -> Decimal {
+        self.checked_sum().expect("Decimal overflow")
+    }
+
+    /// Writes the nearest `f64` of every value into `dst`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `dst` has a different length to the array.
+    pub fn decode_into(&self, dst: &mut [f64]) {
+        assert_eq!(self.mantissas.len(), dst.len(), "Slice length mismatch");
+        let factor = pow10(self.scale) as f64;
+        for (out, &mantissa) in dst.iter_mut().zip(self.mantissas) {
+            *out = mantissa as f64 / factor;
+        }
+    }
+}
+
+impl<'a> Writer<'a> {
+    /// Writes a `Decimal` value to the channel.
+    ///
+    /// Writes the scale as u32 followed by the mantissa as i64.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the channel buffer would overflow.
+    pub fn write_decimal(&mut self, value: Decimal) {
+        self.write_u32(value.scale);
+        self.write_i64(value.mantissa);
+    }
+
+    /// Copies a decimal array to the channel.
+    ///
+    /// Writes the shared scale as u32 followed by the mantissas with length prefix.
+    ///
+    /// # Arguments
+    ///
+    /// * `scale` - The number of decimal places shared by every value
+    /// * `mantissas` - The `i64` mantissas to copy
+    ///
+    /// # Panics
+    ///
+    /// Panics if `scale` is greater than [`MAX_SCALE`] or if the channel buffer would overflow.
+    pub fn copy_array_decimal(&mut self, scale: u32, mantissas: &[i64]) {
+        pow10(scale);
+        self.write_u32(scale);
+        self.copy_array_i64(mantissas);
+    }
+
+    /// Initializes space for a decimal array.
+    ///
+    /// Writes the shared scale and length prefix, then initializes space for the mantissas.
+    ///
+    /// # Returns
+    ///
+    /// A mutable slice of `i64` mantissas.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `scale` is greater than [`MAX_SCALE`] or if the channel buffer would overflow.
+    pub fn init_array_decimal(&mut self, scale: u32, length: u32) -> &mut [i64] {
+        pow10(scale);
+        self.write_u32(scale);
+        self.init_array_i64(length)
+    }
+}
+
+impl<'a> Reader<'a> {
+    /// Reads a `Decimal` value from the channel.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the scale is invalid or if the channel buffer would overflow.
+    pub fn read_decimal(&self) -> Decimal {
+        let scale = self.read_u32();
+        Decimal::new(self.read_i64(), scale)
+    }
+
+    /// Reads a decimal array from the channel.
+    ///
+    /// # Returns
+    ///
+    /// The shared scale and a slice of the `i64` mantissas.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the scale is invalid or if the channel buffer would overflow.
+    pub fn read_array_decimal(&self) -> DecimalArray<'_> {
+        let scale = self.read_u32();
+        pow10(scale);
+        DecimalArray {
+            scale,
+            mantissas: self.read_array_i64(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test;
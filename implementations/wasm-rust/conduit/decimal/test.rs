@@ -0,0 +1,128 @@
+use super::super::{Reader, Writer};
+use super::{Decimal, MAX_SCALE};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decimal_arithmetic_is_exact() {
+        let a = Decimal::from_f64(0.1, 2).unwrap();
+        let b = Decimal::from_f64(0.2, 2).unwrap();
+        assert_eq!(Decimal::new(30, 2), a + b);
+        assert_eq!(Decimal::new(-10, 2), a - b);
+        assert_eq!(Decimal::new(1999 * 7, 2), Decimal::new(1999, 2) * 7);
+        assert_eq!(
+            Decimal::new(1255, 3),
+            Decimal::new(5, 3) + Decimal::new(125, 2)
+        );
+        assert_eq!(3, (Decimal::new(5, 3) + Decimal::new(125, 2)).scale());
+    }
+
+    #[test]
+    fn decimal_rescale() {
+        assert_eq!(Some(Decimal::new(1500, 3)), Decimal::new(15, 1).rescale(3));
+        assert_eq!(Some(Decimal::new(15, 1)), Decimal::new(1500, 3).rescale(1));
+        assert_eq!(None, Decimal::new(1501, 3).rescale(1));
+        assert_eq!(None, Decimal::new(i64::MAX, 0).rescale(1));
+        assert_eq!(
+            None,
+            Decimal::new(i64::MAX, 2).checked_add(Decimal::new(1, 2))
+        );
+        assert_eq!(None, Decimal::new(i64::MIN, 0).checked_mul_int(-1));
+    }
+
+    #[test]
+    fn decimal_try_new_checks_scale() {
+        assert_eq!(
+            Some(Decimal::new(5, MAX_SCALE)),
+            Decimal::try_new(5, MAX_SCALE)
+        );
+        assert_eq!(None, Decimal::try_new(5, MAX_SCALE + 1));
+    }
+
+    #[test]
+    fn decimal_f64_conversion() {
+        assert_eq!(Some(Decimal::new(29, 2)), Decimal::from_f64(0.29, 2));
+        assert_eq!(Some(Decimal::new(-3, 0)), Decimal::from_f64(-2.5, 0));
+        assert_eq!(None, Decimal::from_f64(f64::NAN, 2));
+        assert_eq!(None, Decimal::from_f64(1e17, 4));
+        assert_eq!(12.34, Decimal::new(1234, 2).to_f64());
+    }
+
+    #[test]
+    fn decimal_display() {
+        assert_eq!("12.30", Decimal::new(1230, 2).to_string());
+        assert_eq!("-0.05", Decimal::new(-5, 2).to_string());
+        assert_eq!("42", Decimal::new(42, 0).to_string());
+        assert_eq!(
+            "-9.223372036854775808",
+            Decimal::new(i64::MIN, MAX_SCALE).to_string()
+        );
+    }
+
+    #[cfg(not(feature = "type-tags"))]
+    #[test]
+    fn decimal_value_with_alignment() {
+        let mut storage = [0u64; 32];
+        {
+            let mut writer = Writer::from(&mut storage);
+            writer.write_decimal(Decimal::new(-1, 2));
+        }
+
+        let expected = [
+            2, 0, 0, 0, 0, 0, 0, 0, 255, 255, 255, 255, 255, 255, 255, 255u8,
+        ];
+        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 16) };
+        assert_eq!(&expected[..], actual);
+
+        let mut reader = Reader::from(&mut storage);
+        reader.reset();
+
+        let value = reader.read_decimal();
+        assert_eq!(-1, value.mantissa());
+        assert_eq!(2, value.scale());
+    }
+
+    #[test]
+    fn decimal_array_sum() {
+        let prices = [0.1, 0.2, 0.3, 19.99, 1e-3];
+        let mut storage = [0u64; 32];
+        {
+            let mut writer = Writer::from(&mut storage);
+            let mantissas = writer.init_array_decimal(2, prices.len() as u32);
+            Decimal::encode_slice(&prices, 2, mantissas);
+        }
+
+        let mut reader = Reader::from(&mut storage);
+        reader.reset();
+
+        let array = reader.read_array_decimal();
+        assert_eq!(5, array.len());
+        assert_eq!(Decimal::new(1999, 2), array.get(3));
+        assert_eq!("20.59", array.sum().to_string());
+
+        let mut decoded = [0f64; 5];
+        array.decode_into(&mut decoded);
+        assert_eq!([0.1, 0.2, 0.3, 19.99, 0.0], decoded);
+    }
+
+    #[test]
+    fn decimal_array_sum_uses_wide_accumulator() {
+        let mut storage = [0u64; 32];
+        {
+            let mut writer = Writer::from(&mut storage);
+            writer.copy_array_decimal(0, &[i64::MAX, 1, -2]);
+            writer.copy_array_decimal(0, &[i64::MAX, 1]);
+        }
+
+        let mut reader = Reader::from(&mut storage);
+        reader.reset();
+
+        assert_eq!(
+            Some(Decimal::new(i64::MAX - 1, 0)),
+            reader.read_array_decimal().checked_sum()
+        );
+        assert_eq!(None, reader.read_array_decimal().checked_sum());
+    }
+}
@@ -0,0 +1,265 @@
+use core::fmt;
+
+use super::{Reader, Writer};
+
+/// An IEEE-754 binary16 (half precision) floating point value.
+///
+/// Stored as raw bits so it can live directly in channel storage with 2-byte
+/// alignment. Use [`F16::from_f32`] and [`F16::to_f32`] to convert, or
+/// [`F16::encode_slice`] and [`F16::decode_slice`] for whole slices.
+#[repr(transparent)]
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct F16(pub u16);
+
+/// A bfloat16 floating point value (the upper 16 bits of an `f32`).
+///
+/// Stored as raw bits so it can live directly in channel storage with 2-byte
+/// alignment. Use [`Bf16::from_f32`] and [`Bf16::to_f32`] to convert, or
+/// [`Bf16::encode_slice`] and [`Bf16::decode_slice`] for whole slices.
+#[repr(transparent)]
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Bf16(pub u16);
+
+impl F16 {
+    /// Converts an `f32` to the nearest `F16`, rounding ties to even.
+    ///
+    /// Values too large for half precision become infinity, values too small
+    /// become (signed) zero, and NaN payloads are preserved as a quiet NaN.
+    pub fn from_f32(value: f32) -> Self {
+        let bits = value.to_bits();
+        let sign = ((bits >> 16) & 0x8000) as u16;
+        let exponent = ((bits >> 23) & 0xff) as i32;
+        let mantissa = bits & 0x7f_ffff;
+
+        // Infinity and NaN
+        if exponent == 0xff {
+            let nan = if mantissa != 0 {
+                0x0200 | (mantissa >> 13) as u16
+            } else {
+                0
+            };
+            return Self(sign | 0x7c00 | nan);
+        }
+
+        let half_exponent = exponent - 127 + 15;
+
+        // Overflow to infinity
+        if half_exponent >= 0x1f {
+            return Self(sign | 0x7c00);
+        }
+
+        // Subnormal or underflow to zero
+        if half_exponent <= 0 {
+            if half_exponent < -10 {
+                return Self(sign);
+            }
+
+            let mantissa = mantissa | 0x80_0000;
+            let shift = (14 - half_exponent) as u32;
+            let halfway = 1 << (shift - 1);
+            let remainder = mantissa & ((1 << shift) - 1);
+            let mut result = (mantissa >> shift) as u16;
+
+            if remainder > halfway || (remainder == halfway && result & 1 == 1) {
+                result += 1;
+            }
+
+            return Self(sign | result);
+        }
+
+        let mut result = ((half_exponent as u32) << 10 | (mantissa >> 13)) as u16;
+        let remainder = mantissa & 0x1fff;
+
+        // A carry out of the mantissa correctly bumps the exponent (up to infinity)
+        if remainder > 0x1000 || (remainder == 0x1000 && result & 1 == 1) {
+            result += 1;
+        }
+
+        Self(sign | result)
+    }
+
+    /// Converts this value to an `f32`. The conversion is exact.
+    pub fn to_f32(self) -> f32 {
+        let sign = ((self.0 & 0x8000) as u32) << 16;
+        let exponent = ((self.0 >> 10) & 0x1f) as u32;
+        let mantissa = (self.0 & 0x3ff) as u32;
+
+        match exponent {
+            0 => {
+                // Zero or subnormal: mantissa * 2^-24
+                let magnitude = mantissa as f32 * (1.0 / 16_777_216.0);
+                f32::from_bits(sign | magnitude.to_bits())
+            }
+            0x1f => f32::from_bits(sign | 0x7f80_0000 | (mantissa << 13)),
+            _ => f32::from_bits(sign | ((exponent + 112) << 23) | (mantissa << 13)),
+        }
+    }
+
+    /// Converts each `f32` in `src` into the corresponding slot of `dst`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `src` and `dst` have different lengths.
+    pub fn encode_slice(src: &[f32], dst: &mut [F16]) {
+        assert_eq!(src.len(), dst.len(), "Slice length mismatch");
+        for (out, &value) in dst.iter_mut().zip(src) {
+            *out = Self::from_f32(value);
+        }
+    }
+
+    /// Converts each `F16` in `src` into the corresponding slot of `dst`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `src` and `dst` have different lengths.
+    pub fn decode_slice(src: &[F16], dst: &mut [f32]) {
+        assert_eq!(src.len(), dst.len(), "Slice length mismatch");
+        for (out, value) in dst.iter_mut().zip(src) {
+            *out = value.to_f32();
+        }
+    }
+}
+
+impl Bf16 {
+    /// Converts an `f32` to the nearest `Bf16`, rounding ties to even.
+    ///
+    /// NaN values are kept as a quiet NaN rather than being rounded to infinity.
+    pub fn from_f32(value: f32) -> Self {
+        let bits = value.to_bits();
+
+        if value.is_nan() {
+            return Self(((bits >> 16) as u16) | 0x0040);
+        }
+
+        let rounding_bias = 0x7fff + ((bits >> 16) & 1);
+        Self((bits.wrapping_add(rounding_bias) >> 16) as u16)
+    }
+
+    /// Converts this value to an `f32`. The conversion is exact.
+    pub fn to_f32(self) -> f32 {
+        f32::from_bits((self.0 as u32) << 16)
+    }
+
+    /// Converts each `f32` in `src` into the corresponding slot of `dst`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `src` and `dst` have different lengths.
+    pub fn encode_slice(src: &[f32], dst: &mut [Bf16]) {
+        assert_eq!(src.len(), dst.len(), "Slice length mismatch");
+        for (out, &value) in dst.iter_mut().zip(src) {
+            *out = Self::from_f32(value);
+        }
+    }
+
+    /// Converts each `Bf16` in `src` into the corresponding slot of `dst`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `src` and `dst` have different lengths.
+    pub fn decode_slice(src: &[Bf16], dst: &mut [f32]) {
+        assert_eq!(src.len(), dst.len(), "Slice length mismatch");
+        for (out, value) in dst.iter_mut().zip(src) {
+            *out = value.to_f32();
+        }
+    }
+}
+
+impl From<f32> for F16 {
+    fn from(value: f32) -> Self {
+        Self::from_f32(value)
+    }
+}
+
+impl From<F16> for f32 {
+    fn from(value: F16) -> Self {
+        value.to_f32()
+    }
+}
+
+impl From<f32> for Bf16 {
+    fn from(value: f32) -> Self {
+        Self::from_f32(value)
+    }
+}
+
+impl From<Bf16> for f32 {
+    fn from(value: Bf16) -> Self {
+        value.to_f32()
+    }
+}
+
+impl fmt::Debug for F16 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "F16({:?})", self.to_f32())
+    }
+}
+
+impl fmt::Debug for Bf16 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Bf16({:?})", self.to_f32())
+    }
+}
+
+impl<'a> Writer<'a> {
+    /// Converts an `f32` slice to `F16` and writes it to the channel with length prefix.
+    ///
+    /// Equivalent to `init_array_f16` followed by [`F16::encode_slice`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the channel buffer would overflow.
+    pub fn copy_array_f16_from_f32(&mut self, arr: &[f32]) {
+        let dst = self.init_array_f16(arr.len() as u32);
+        F16::encode_slice(arr, dst);
+    }
+
+    /// Converts an `f32` slice to `Bf16` and writes it to the channel with length prefix.
+    ///
+    /// Equivalent to `init_array_bf16` followed by [`Bf16::encode_slice`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the channel buffer would overflow.
+    pub fn copy_array_bf16_from_f32(&mut self, arr: &[f32]) {
+        let dst = self.init_array_bf16(arr.len() as u32);
+        Bf16::encode_slice(arr, dst);
+    }
+}
+
+impl<'a> Reader<'a> {
+    /// Reads an `F16` array with length prefix, converting it into `dst`.
+    ///
+    /// # Returns
+    ///
+    /// The number of elements read.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the channel buffer would overflow or if `dst` is shorter than
+    /// the array.
+    pub fn read_array_f16_into_f32(&self, dst: &mut [f32]) -> usize {
+        let src = self.read_array_f16();
+        F16::decode_slice(src, &mut dst[..src.len()]);
+        src.len()
+    }
+
+    /// Reads a `Bf16` array with length prefix, converting it into `dst`.
+    ///
+    /// # Returns
+    ///
+    /// The number of elements read.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the channel buffer would overflow or if `dst` is shorter than
+    /// the array.
+    pub fn read_array_bf16_into_f32(&self, dst: &mut [f32]) -> usize {
+        let src = self.read_array_bf16();
+        Bf16::decode_slice(src, &mut dst[..src.len()]);
+        src.len()
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,130 @@
+use super::super::{Reader, Writer};
+use super::{Bf16, F16};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn f16_exact_values() {
+        assert_eq!(0x0000, F16::from_f32(0.0).0);
+        assert_eq!(0x8000, F16::from_f32(-0.0).0);
+        assert_eq!(0x3c00, F16::from_f32(1.0).0);
+        assert_eq!(0xc000, F16::from_f32(-2.0).0);
+        assert_eq!(0x7bff, F16::from_f32(65504.0).0);
+        assert_eq!(0x0001, F16::from_f32(5.960_464_5e-8).0);
+
+        assert_eq!(1.0, F16(0x3c00).to_f32());
+        assert_eq!(65504.0, F16(0x7bff).to_f32());
+        assert_eq!(5.960_464_5e-8, F16(0x0001).to_f32());
+    }
+
+    #[test]
+    fn f16_special_values() {
+        assert_eq!(0x7c00, F16::from_f32(f32::INFINITY).0);
+        assert_eq!(0xfc00, F16::from_f32(f32::NEG_INFINITY).0);
+        assert_eq!(0x7c00, F16::from_f32(1e6).0);
+        assert_eq!(0x0000, F16::from_f32(1e-10).0);
+        assert!(F16::from_f32(f32::NAN).to_f32().is_nan());
+        assert_eq!(f32::INFINITY, F16(0x7c00).to_f32());
+    }
+
+    #[test]
+    fn f16_rounds_ties_to_even() {
+        // 1 + 2^-11 is halfway between 1.0 and the next f16, so rounds down to even
+        assert_eq!(0x3c00, F16::from_f32(1.0 + 2f32.powi(-11)).0);
+        // 1 + 3 * 2^-11 is halfway between two f16s, so rounds up to even
+        assert_eq!(0x3c02, F16::from_f32(1.0 + 3.0 * 2f32.powi(-11)).0);
+        // Rounding the largest finite value up overflows to infinity
+        assert_eq!(0x7c00, F16::from_f32(65520.0).0);
+    }
+
+    #[test]
+    fn f16_round_trips_every_finite_value() {
+        for bits in 0..=u16::MAX {
+            let value = F16(bits);
+            if value.to_f32().is_nan() {
+                continue;
+            }
+            assert_eq!(bits, F16::from_f32(value.to_f32()).0);
+        }
+    }
+
+    #[test]
+    fn bf16_conversions() {
+        assert_eq!(0x3f80, Bf16::from_f32(1.0).0);
+        assert_eq!(0xc000, Bf16::from_f32(-2.0).0);
+        assert_eq!(0x7f80, Bf16::from_f32(f32::INFINITY).0);
+        assert!(Bf16::from_f32(f32::NAN).to_f32().is_nan());
+        // 1 + 2^-8 is halfway, rounds to even (1.0)
+        assert_eq!(0x3f80, Bf16::from_f32(1.0 + 2f32.powi(-8)).0);
+        // 1 + 3 * 2^-8 is halfway, rounds to even (1 + 2^-6)
+        assert_eq!(0x3f82, Bf16::from_f32(1.0 + 3.0 * 2f32.powi(-8)).0);
+        assert_eq!(3.140625, Bf16(0x4049).to_f32());
+    }
+
+    #[cfg(not(feature = "type-tags"))]
+    #[test]
+    fn f16_with_alignment() {
+        let mut storage = [0u64; 32];
+        {
+            let mut writer = Writer::from(&mut storage);
+            writer.write_u8(7);
+            writer.write_f16(F16::from_f32(1.0));
+            writer.write_bf16(Bf16::from_f32(-2.0));
+        }
+
+        let expected = [7, 0, 0x00, 0x3c, 0x00, 0xc0, 0, 0u8];
+        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 8) };
+        assert_eq!(&expected[..], actual);
+
+        let mut reader = Reader::from(&mut storage);
+        reader.reset();
+
+        assert_eq!(7, reader.read_u8());
+        assert_eq!(1.0, reader.read_f16().to_f32());
+        assert_eq!(-2.0, reader.read_bf16().to_f32());
+    }
+
+    #[cfg(not(feature = "type-tags"))]
+    #[test]
+    fn f16_array_from_f32() {
+        let mut storage = [0u64; 32];
+        {
+            let mut writer = Writer::from(&mut storage);
+            writer.write_u8(1);
+            writer.copy_array_f16_from_f32(&[0.5, -1.5, 2.0]);
+        }
+
+        let expected = [
+            1, 0, 0, 0, 3, 0, 0, 0, 0x00, 0x38, 0x00, 0xbe, 0x00, 0x40, 0, 0u8,
+        ];
+        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 16) };
+        assert_eq!(&expected[..], actual);
+
+        let mut reader = Reader::from(&mut storage);
+        reader.reset();
+
+        let mut values = [0f32; 4];
+        assert_eq!(1, reader.read_u8());
+        assert_eq!(3, reader.read_array_f16_into_f32(&mut values));
+        assert_eq!([0.5, -1.5, 2.0, 0.0], values);
+    }
+
+    #[test]
+    fn bf16_array_from_f32() {
+        let mut storage = [0u64; 32];
+        {
+            let mut writer = Writer::from(&mut storage);
+            writer.copy_array_bf16_from_f32(&[1.0, 3.140625]);
+        }
+
+        let mut reader = Reader::from(&mut storage);
+        reader.reset();
+
+        let values = reader.read_array_bf16();
+        let mut decoded = [0f32; 2];
+        Bf16::decode_slice(values, &mut decoded);
+        assert_eq!([1.0, 3.140625], decoded);
+    }
+}
@@ -0,0 +1,192 @@
+use core::fmt;
+
+use super::{Reader, Writer, TYPE_TAGS_ENABLED};
+
+/// Magic bytes identifying a conduit message header (`"ZAWM"` in little-endian order).
+pub const HEADER_MAGIC: u32 = u32::from_le_bytes(*b"ZAWM");
+
+/// The version of the conduit wire protocol written into message headers.
+pub const PROTOCOL_VERSION: u16 = 1;
+
+/// The size of a message header in bytes.
+pub const HEADER_SIZE: u32 = 16;
+
+/// Header flag set when every value in the payload is prefixed with a type tag.
+pub const FLAG_TYPE_TAGS: u16 = 1;
+
+/// A message header, written at the start of a channel in header mode.
+///
+/// The wire layout is magic (`Uint32`), version (`Uint16`), flags (`Uint16`),
+/// schema id (`Uint32`) and payload length in bytes (`Uint32`). The header
+/// itself is never type tagged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Header {
+    pub version: u16,
+    pub flags: u16,
+    pub schema_id: u32,
+    pub payload_length: u32,
+}
+
+/// An invalid or mismatched message header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeaderError {
+    /// The channel does not start with [`HEADER_MAGIC`].
+    BadMagic { found: u32 },
+    /// The message was written with a different protocol version.
+    VersionMismatch { expected: u16, found: u16 },
+    /// The message was written for a different schema.
+    SchemaMismatch { expected: u32, found: u32 },
+    /// The payload length is larger than the channel.
+    PayloadTooLarge { length: u32, capacity: u32 },
+    /// One side was built with type tags and the other without.
+    TypeTagsMismatch { expected: bool, found: bool },
+}
+
+impl fmt::Display for HeaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            HeaderError::BadMagic { found } => {
+                write!(f, "Invalid message header: bad magic 0x{:08x}", found)
+            }
+            HeaderError::VersionMismatch { expected, found } => write!(
+                f,
+                "Protocol version mismatch: expected {} but found {}",
+                expected, found
+            ),
+            HeaderError::SchemaMismatch { expected, found } => write!(
+                f,
+                "Schema mismatch: expected {} but found {}",
+                expected, found
+            ),
+            HeaderError::PayloadTooLarge { length, capacity } => write!(
+                f,
+                "Payload length {} exceeds channel capacity {}",
+                length, capacity
+            ),
+            HeaderError::TypeTagsMismatch { expected, found } => write!(
+                f,
+                "Type tags mismatch: expected {} but found {}",
+                if expected { "tagged" } else { "untagged" },
+                if found { "tagged" } else { "untagged" }
+            ),
+        }
+    }
+}
+
+impl core::error::Error for HeaderError {}
+
+impl<'a> Writer<'a> {
+    /// Writes a message header at the start of the channel.
+    ///
+    /// The payload length is left as zero until [`Writer::end_message`] is called.
+    ///
+    /// # Arguments
+    ///
+    /// * `schema_id` - An application-defined id that the reader must expect
+    ///
+    /// # Panics
+    ///
+    /// Panics if anything has already been written since the last reset.
+    pub fn begin_message(&mut self, schema_id: u32) {
+        if self.channel.offset.get() != self.channel.start {
+            panic!("Message header must be written at the start of the channel");
+        }
+
+        let flags = if TYPE_TAGS_ENABLED { FLAG_TYPE_TAGS } else { 0 };
+
+        // The header is never tagged so its layout is fixed
+        self.channel.put::<u32>(HEADER_MAGIC);
+        self.channel.put::<u16>(PROTOCOL_VERSION);
+        self.channel.put::<u16>(flags);
+        self.channel.put::<u32>(schema_id);
+        self.channel.put::<u32>(0);
+    }
+
+    /// Writes the payload length into the message header.
+    ///
+    /// # Returns
+    ///
+    /// The payload length in bytes, excluding the header.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no message header was written.
+    pub fn end_message(&mut self) -> u32 {
+        let start = self.channel.start as usize;
+        if self.channel.storage_u32[start >> 2] != HEADER_MAGIC {
+            panic!("No message header to end");
+        }
+
+        let length = self.channel.offset.get() - self.channel.start - HEADER_SIZE;
+        self.channel.storage_u32[(start >> 2) + 3] = length;
+
+        length
+    }
+}
+
+impl<'a> Reader<'a> {
+    /// Reads and validates a message header at the start of the channel.
+    ///
+    /// On success the reader is bounded to the payload, so reading beyond the
+    /// length the writer recorded panics instead of returning stale data.
+    ///
+    /// # Arguments
+    ///
+    /// * `schema_id` - The schema id the writer must have used
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`HeaderError`] if the magic, protocol version, schema id or
+    /// type tag flag do not match, or if the payload length exceeds the channel.
+    pub fn read_message_header(&self, schema_id: u32) -> Result<Header, HeaderError> {
+        let magic = self.channel.take::<u32>();
+        if magic != HEADER_MAGIC {
+            return Err(HeaderError::BadMagic { found: magic });
+        }
+
+        let header = Header {
+            version: self.channel.take::<u16>(),
+            flags: self.channel.take::<u16>(),
+            schema_id: self.channel.take::<u32>(),
+            payload_length: self.channel.take::<u32>(),
+        };
+
+        if header.version != PROTOCOL_VERSION {
+            return Err(HeaderError::VersionMismatch {
+                expected: PROTOCOL_VERSION,
+                found: header.version,
+            });
+        }
+
+        if header.schema_id != schema_id {
+            return Err(HeaderError::SchemaMismatch {
+                expected: schema_id,
+                found: header.schema_id,
+            });
+        }
+
+        let tagged = header.flags & FLAG_TYPE_TAGS != 0;
+        if tagged != TYPE_TAGS_ENABLED {
+            return Err(HeaderError::TypeTagsMismatch {
+                expected: TYPE_TAGS_ENABLED,
+                found: tagged,
+            });
+        }
+
+        let offset = self.channel.offset.get();
+        let capacity = self.channel.limit.get() - offset;
+        if header.payload_length > capacity {
+            return Err(HeaderError::PayloadTooLarge {
+                length: header.payload_length,
+                capacity,
+            });
+        }
+
+        self.channel.limit.set(offset + header.payload_length);
+
+        Ok(header)
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,129 @@
+use super::super::{Reader, Writer};
+#[cfg_attr(feature = "type-tags", allow(unused_imports))]
+use super::{HeaderError, HEADER_MAGIC, PROTOCOL_VERSION};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(not(feature = "type-tags"))]
+    #[test]
+    fn message_header_round_trip() {
+        let mut storage = [0u64; 32];
+        {
+            let mut writer = Writer::from(&mut storage);
+            writer.begin_message(7);
+            writer.write_u8(1);
+            writer.write_f64(2.5);
+            assert_eq!(16, writer.end_message());
+        }
+
+        let expected = [
+            90, 65, 87, 77, 1, 0, 0, 0, 7, 0, 0, 0, 16, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0u8,
+        ];
+        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 24) };
+        assert_eq!(&expected[..], actual);
+
+        let mut reader = Reader::from(&mut storage);
+        reader.reset();
+
+        let header = reader.read_message_header(7).unwrap();
+        assert_eq!(PROTOCOL_VERSION, header.version);
+        assert_eq!(16, header.payload_length);
+        assert_eq!(1, reader.read_u8());
+        assert_eq!(2.5, reader.read_f64());
+    }
+
+    #[cfg(not(feature = "type-tags"))]
+    #[test]
+    fn message_header_mismatches() {
+        let mut storage = [0u64; 8];
+        {
+            let mut writer = Writer::from(&mut storage);
+            writer.write_u32(0x1234);
+        }
+        let mut reader = Reader::from(&mut storage);
+        assert_eq!(
+            Err(HeaderError::BadMagic { found: 0x1234 }),
+            reader.read_message_header(1)
+        );
+
+        {
+            let mut writer = Writer::from(&mut storage);
+            writer.begin_message(1);
+        }
+        reader = Reader::from(&mut storage);
+        assert_eq!(
+            Err(HeaderError::SchemaMismatch {
+                expected: 2,
+                found: 1
+            }),
+            reader.read_message_header(2)
+        );
+
+        {
+            let mut writer = Writer::from(&mut storage);
+            writer.write_u32(HEADER_MAGIC);
+            writer.write_u16(PROTOCOL_VERSION + 1);
+        }
+        reader = Reader::from(&mut storage);
+        assert_eq!(
+            Err(HeaderError::VersionMismatch {
+                expected: PROTOCOL_VERSION,
+                found: PROTOCOL_VERSION + 1
+            }),
+            reader.read_message_header(1)
+        );
+
+        {
+            let mut writer = Writer::from(&mut storage);
+            writer.write_u32(HEADER_MAGIC);
+            writer.write_u16(PROTOCOL_VERSION);
+            writer.write_u16(0);
+            writer.write_u32(1);
+            writer.write_u32(49);
+        }
+        reader = Reader::from(&mut storage);
+        assert_eq!(
+            Err(HeaderError::PayloadTooLarge {
+                length: 49,
+                capacity: 48
+            }),
+            reader.read_message_header(1)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Channel buffer overflow")]
+    fn reading_past_payload_panics() {
+        let mut storage = [0u64; 32];
+        {
+            let mut writer = Writer::from(&mut storage);
+            writer.begin_message(1);
+            writer.write_u32(1);
+            writer.end_message();
+        }
+
+        let reader = Reader::from(&mut storage);
+        reader.read_message_header(1).unwrap();
+        reader.read_u32();
+        reader.read_u32();
+    }
+
+    #[cfg(not(feature = "type-tags"))]
+    #[test]
+    fn reset_restores_capacity() {
+        let mut storage = [0u64; 32];
+        {
+            let mut writer = Writer::from(&mut storage);
+            writer.begin_message(1);
+            writer.end_message();
+        }
+
+        let mut reader = Reader::from(&mut storage);
+        reader.read_message_header(1).unwrap();
+        reader.reset();
+        assert_eq!(HEADER_MAGIC, reader.read_u32());
+        reader.read_elements_u8(200);
+    }
+}
@@ -0,0 +1,396 @@
+use core::fmt;
+
+#[cfg(feature = "journal")]
+use core::cell::Ref;
+
+use super::tags::Tagged;
+use super::{Channel, Reader, TagKind, Writer};
+
+#[cfg(feature = "journal")]
+use super::{Bf16, TypeTag, F16};
+
+/// The number of entries a channel journal keeps before overwriting the oldest.
+#[cfg(feature = "journal")]
+pub const JOURNAL_CAPACITY: usize = 64;
+
+/// The number of leading bytes of each operation a journal entry keeps.
+#[cfg(feature = "journal")]
+pub const JOURNAL_PREVIEW_BYTES: usize = 16;
+
+/// The kind of channel operation recorded in a journal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JournalOp {
+    #[default]
+    Write,
+    Read,
+    Init,
+    Advance,
+}
+
+impl fmt::Display for JournalOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            JournalOp::Write => "write",
+            JournalOp::Read => "read",
+            JournalOp::Init => "init",
+            JournalOp::Advance => "advance",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A single recorded channel operation.
+#[cfg(feature = "journal")]
+#[derive(Debug, Clone, Copy)]
+pub struct JournalEntry {
+    /// The position of this entry since the journal was last cleared.
+    pub sequence: u32,
+    pub op: JournalOp,
+    pub tag: TypeTag,
+    pub kind: TagKind,
+    /// The byte offset of the first element.
+    pub offset: u32,
+    /// The number of elements.
+    pub count: u32,
+    preview: [u8; JOURNAL_PREVIEW_BYTES],
+    preview_len: u8,
+}
+
+#[cfg(feature = "journal")]
+impl JournalEntry {
+    const EMPTY: JournalEntry = JournalEntry {
+        sequence: 0,
+        op: JournalOp::Write,
+        tag: TypeTag::Uint8,
+        kind: TagKind::Value,
+        offset: 0,
+        count: 0,
+        preview: [0; JOURNAL_PREVIEW_BYTES],
+        preview_len: 0,
+    };
+
+    /// Returns the leading bytes of the values written or read.
+    ///
+    /// Empty for `init` operations, since the values are filled in afterwards.
+    pub fn preview(&self) -> &[u8] {
+        &self.preview[..self.preview_len as usize]
+    }
+}
+
+#[cfg(feature = "journal")]
+impl fmt::Display for JournalEntry {
+    /// Formats the entry, e.g. `#3 read Float64 elements @16 x4 [1.5, 2, 2.5, 3]`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self.kind {
+            TagKind::Value => "value",
+            TagKind::Elements => "elements",
+        };
+        write!(
+            f,
+            "#{} {} {} {} @{} x{}",
+            self.sequence,
+            self.op,
+            self.tag.name(),
+            kind,
+            self.offset,
+            self.count
+        )?;
+
+        if self.preview_len > 0 {
+            f.write_str(" [")?;
+            let shown = write_values(f, self.tag, self.preview())?;
+            if shown < self.count as usize {
+                f.write_str(", ...")?;
+            }
+            f.write_str("]")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A bounded journal of channel operations, for debugging encodings.
+///
+/// Enable it on a `Writer` or `Reader` with `enable_journal` (requires the
+/// `journal` feature). Once full, the oldest entries are overwritten. The
+/// journal is cleared whenever the channel is reset.
+#[cfg(feature = "journal")]
+#[derive(Debug, Clone)]
+pub struct Journal {
+    entries: [JournalEntry; JOURNAL_CAPACITY],
+    recorded: u32,
+    enabled: bool,
+}
+
+#[cfg(feature = "journal")]
+impl Journal {
+    pub(super) fn new() -> Self {
+        Self {
+            entries: [JournalEntry::EMPTY; JOURNAL_CAPACITY],
+            recorded: 0,
+            enabled: false,
+        }
+    }
+
+    /// Returns `true` if operations are being recorded.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Returns the number of entries currently held.
+    pub fn len(&self) -> usize {
+        (self.recorded as usize).min(JOURNAL_CAPACITY)
+    }
+
+    /// Returns `true` if no entries are held.
+    pub fn is_empty(&self) -> bool {
+        self.recorded == 0
+    }
+
+    /// Returns the number of entries overwritten because the journal was full.
+    pub fn dropped(&self) -> u32 {
+        self.recorded.saturating_sub(JOURNAL_CAPACITY as u32)
+    }
+
+    /// Removes all entries.
+    pub fn clear(&mut self) {
+        self.recorded = 0;
+    }
+
+    /// Iterates over the held entries, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = &JournalEntry> {
+        let first = self.dropped();
+        (first..self.recorded)
+            .map(move |sequence| &self.entries[sequence as usize % JOURNAL_CAPACITY])
+    }
+
+    fn push(&mut self, entry: JournalEntry) {
+        self.entries[self.recorded as usize % JOURNAL_CAPACITY] = entry;
+        self.recorded = self.recorded.wrapping_add(1);
+    }
+}
+
+#[cfg(feature = "journal")]
+impl fmt::Display for Journal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.dropped() > 0 {
+            writeln!(f, "({} earlier entries dropped)", self.dropped())?;
+        }
+        for entry in self.iter() {
+            writeln!(f, "{}", entry)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> Channel<'a> {
+    /// Records an operation on `count` elements starting at element `index`,
+    /// when the `journal` feature is enabled and the journal is switched on.
+    #[inline(always)]
+    #[allow(unused_variables)]
+    pub(super) fn record<T: Tagged>(&self, op: JournalOp, kind: TagKind, index: usize, count: u32) {
+        #[cfg(feature = "journal")]
+        {
+            let mut journal = self.journal.borrow_mut();
+            if !journal.enabled {
+                return;
+            }
+
+            let offset = index * core::mem::size_of::<T>();
+            let preview_len = if op == JournalOp::Init {
+                0
+            } else {
+                (count as usize * core::mem::size_of::<T>()).min(JOURNAL_PREVIEW_BYTES)
+            };
+
+            let mut entry = JournalEntry {
+                sequence: journal.recorded,
+                op,
+                tag: T::TAG,
+                kind,
+                offset: offset as u32,
+                count,
+                preview: [0; JOURNAL_PREVIEW_BYTES],
+                preview_len: preview_len as u8,
+            };
+            entry.preview[..preview_len]
+                .copy_from_slice(&self.storage_u8[offset..offset + preview_len]);
+
+            journal.push(entry);
+        }
+    }
+}
+
+/// Writes the whole values contained in `bytes`, returning how many were written.
+#[cfg(feature = "journal")]
+fn write_values(
+    f: &mut fmt::Formatter<'_>,
+    tag: TypeTag,
+    bytes: &[u8],
+) -> Result<usize, fmt::Error> {
+    macro_rules! write_all {
+        ($size:expr, |$chunk:ident| $value:expr) => {{
+            for (i, $chunk) in bytes.chunks_exact($size).enumerate() {
+                if i > 0 {
+                    f.write_str(", ")?;
+                }
+                write!(f, "{}", $value)?;
+            }
+            Ok(bytes.len() / $size)
+        }};
+    }
+
+    match tag {
+        TypeTag::Uint8 => write_all!(1, |b| b[0]),
+        TypeTag::Int8 => write_all!(1, |b| b[0] as i8),
+        TypeTag::Uint16 => write_all!(2, |b| u16::from_le_bytes([b[0], b[1]])),
+        TypeTag::Float16 => write_all!(2, |b| F16(u16::from_le_bytes([b[0], b[1]])).to_f32()),
+        TypeTag::BFloat16 => write_all!(2, |b| Bf16(u16::from_le_bytes([b[0], b[1]])).to_f32()),
+        TypeTag::Uint32 => write_all!(4, |b| u32::from_le_bytes([b[0], b[1], b[2], b[3]])),
+        TypeTag::Int32 | TypeTag::Date32 => {
+            write_all!(4, |b| i32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        }
+        TypeTag::Float32 => write_all!(4, |b| f32::from_le_bytes([b[0], b[1], b[2], b[3]])),
+        TypeTag::Int64 | TypeTag::TimestampMillis | TypeTag::Duration => {
+            write_all!(8, |b| i64::from_le_bytes([
+                b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]
+            ]))
+        }
+        TypeTag::Float64 => {
+            write_all!(8, |b| f64::from_le_bytes([
+                b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]
+            ]))
+        }
+    }
+}
+
+/// A hex dump of channel bytes, optionally annotated with journal entries.
+///
+/// Each line shows the byte offset, 16 bytes in hex and their printable ASCII.
+/// With a journal attached, the entries that start on a line are listed under it.
+pub struct HexDump<'a> {
+    bytes: &'a [u8],
+    base: u32,
+    #[cfg(feature = "journal")]
+    journal: Option<&'a Journal>,
+}
+
+impl<'a> HexDump<'a> {
+    /// Creates a hex dump of `bytes`, labelling the first byte as offset `base`.
+    pub fn new(bytes: &'a [u8], base: u32) -> Self {
+        Self {
+            bytes,
+            base,
+            #[cfg(feature = "journal")]
+            journal: None,
+        }
+    }
+
+    /// Annotates the dump with the entries of `journal`.
+    #[cfg(feature = "journal")]
+    pub fn with_journal(mut self, journal: &'a Journal) -> Self {
+        self.journal = Some(journal);
+        self
+    }
+}
+
+impl<'a> fmt::Display for HexDump<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (row, chunk) in self.bytes.chunks(16).enumerate() {
+            let start = self.base + row as u32 * 16;
+            write!(f, "{:08x} ", start)?;
+            for i in 0..16 {
+                match chunk.get(i) {
+                    Some(byte) => write!(f, " {:02x}", byte)?,
+                    None => f.write_str("   ")?,
+                }
+            }
+            f.write_str("  |")?;
+            for &byte in chunk {
+                let c = if byte.is_ascii_graphic() || byte == b' ' {
+                    byte as char
+                } else {
+                    '.'
+                };
+                write!(f, "{}", c)?;
+            }
+            writeln!(f, "|")?;
+
+            #[cfg(feature = "journal")]
+            if let Some(journal) = self.journal {
+                let end = start + chunk.len() as u32;
+                for entry in journal
+                    .iter()
+                    .filter(|e| e.offset >= start && e.offset < end)
+                {
+                    writeln!(f, "         ^ {}", entry)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a> Writer<'a> {
+    /// Returns a hex dump of everything written since the last reset.
+    pub fn hex_dump(&self) -> HexDump<'_> {
+        let start = self.channel.start as usize;
+        let end = self.channel.offset.get() as usize;
+        HexDump::new(&self.channel.storage_u8[start..end], start as u32)
+    }
+
+    /// Starts recording every operation into the channel journal.
+    #[cfg(feature = "journal")]
+    pub fn enable_journal(&mut self) {
+        self.channel.journal.get_mut().enabled = true;
+    }
+
+    /// Stops recording operations. Existing entries are kept.
+    #[cfg(feature = "journal")]
+    pub fn disable_journal(&mut self) {
+        self.channel.journal.get_mut().enabled = false;
+    }
+
+    /// Returns the channel journal.
+    #[cfg(feature = "journal")]
+    pub fn journal(&self) -> Ref<'_, Journal> {
+        self.channel.journal.borrow()
+    }
+}
+
+impl<'a> Reader<'a> {
+    /// Returns a hex dump of the first `length` bytes of the channel.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `length` extends beyond the channel.
+    pub fn hex_dump(&self, length: u32) -> HexDump<'_> {
+        let start = self.channel.start as usize;
+        let end = start + length as usize;
+        if end > self.channel.end as usize {
+            panic!("Channel buffer overflow");
+        }
+        HexDump::new(&self.channel.storage_u8[start..end], start as u32)
+    }
+
+    /// Starts recording every operation into the channel journal.
+    #[cfg(feature = "journal")]
+    pub fn enable_journal(&mut self) {
+        self.channel.journal.get_mut().enabled = true;
+    }
+
+    /// Stops recording operations. Existing entries are kept.
+    #[cfg(feature = "journal")]
+    pub fn disable_journal(&mut self) {
+        self.channel.journal.get_mut().enabled = false;
+    }
+
+    /// Returns the channel journal.
+    #[cfg(feature = "journal")]
+    pub fn journal(&self) -> Ref<'_, Journal> {
+        self.channel.journal.borrow()
+    }
+}
+
+#[cfg(test)]
+mod test;
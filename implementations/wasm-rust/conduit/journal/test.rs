@@ -0,0 +1,135 @@
+#[cfg_attr(feature = "type-tags", allow(unused_imports))]
+use super::super::{Reader, Writer};
+
+#[cfg(all(feature = "journal", not(feature = "type-tags")))]
+use super::JournalOp;
+
+#[cfg(test)]
+mod tests {
+    #[cfg_attr(feature = "type-tags", allow(unused_imports))]
+    use super::*;
+
+    #[cfg(not(feature = "type-tags"))]
+    #[test]
+    fn hex_dump_of_written_bytes() {
+        let mut storage = [0u64; 32];
+        let mut writer = Writer::from(&mut storage);
+        writer.write_u32(0x6c6c6548);
+        writer.write_u32(0x0000216f);
+        writer.write_u8(255);
+
+        let expected = "00000000  48 65 6c 6c 6f 21 00 00 ff                       |Hello!...|\n";
+        assert_eq!(expected, writer.hex_dump().to_string());
+    }
+
+    #[cfg(not(feature = "type-tags"))]
+    #[test]
+    fn hex_dump_of_reader_bytes() {
+        let mut storage = [0u64; 32];
+        {
+            let mut writer = Writer::from(&mut storage);
+            for i in 0..5 {
+                writer.write_u32(i);
+            }
+        }
+
+        let reader = Reader::from(&mut storage);
+        let dump = reader.hex_dump(20).to_string();
+        let lines: Vec<&str> = dump.lines().collect();
+
+        assert_eq!(2, lines.len());
+        assert!(lines[0].starts_with("00000000  00 00 00 00 01 00 00 00"));
+        assert!(lines[1].starts_with("00000010  04 00 00 00"));
+    }
+
+    #[cfg(not(feature = "type-tags"))]
+    #[test]
+    #[cfg(feature = "journal")]
+    fn journal_records_writes_and_reads() {
+        let mut storage = [0u64; 32];
+        {
+            let mut writer = Writer::from(&mut storage);
+            writer.enable_journal();
+            writer.write_u8(7);
+            writer.write_f64(2.5);
+            writer.copy_array_i32(&[1, -2, 3]);
+            writer.init_array_f32(2);
+
+            let journal = writer.journal();
+            let lines: Vec<String> = journal.iter().map(|e| e.to_string()).collect();
+            assert_eq!(
+                vec![
+                    "#0 write Uint8 value @0 x1 [7]",
+                    "#1 write Float64 value @8 x1 [2.5]",
+                    "#2 write Uint32 value @16 x1 [3]",
+                    "#3 write Int32 elements @20 x3 [1, -2, 3]",
+                    "#4 write Uint32 value @32 x1 [2]",
+                    "#5 init Float32 elements @36 x2",
+                ],
+                lines
+            );
+        }
+
+        let mut reader = Reader::from(&mut storage);
+        reader.enable_journal();
+        reader.read_u8();
+        reader.read_f64();
+
+        let journal = reader.journal();
+        assert_eq!(2, journal.len());
+        let entry = journal.iter().nth(1).unwrap();
+        assert_eq!(JournalOp::Read, entry.op);
+        assert_eq!(8, entry.offset);
+        assert_eq!(&2.5f64.to_le_bytes()[..], entry.preview());
+    }
+
+    #[test]
+    #[cfg(feature = "journal")]
+    fn journal_is_bounded_and_cleared_on_reset() {
+        let mut storage = [0u64; 128];
+        let mut writer = Writer::from(&mut storage);
+        writer.enable_journal();
+        for i in 0..(super::super::JOURNAL_CAPACITY as u32 + 10) {
+            writer.write_u32(i);
+        }
+
+        {
+            let journal = writer.journal();
+            assert_eq!(super::super::JOURNAL_CAPACITY, journal.len());
+            assert_eq!(10, journal.dropped());
+            assert_eq!(10, journal.iter().next().unwrap().sequence);
+        }
+
+        writer.reset();
+        assert!(writer.journal().is_empty());
+        assert!(writer.journal().is_enabled());
+    }
+
+    #[test]
+    #[cfg(feature = "journal")]
+    fn journal_is_off_by_default() {
+        let mut storage = [0u64; 32];
+        let mut writer = Writer::from(&mut storage);
+        writer.write_u32(1);
+        assert!(writer.journal().is_empty());
+    }
+
+    #[cfg(not(feature = "type-tags"))]
+    #[test]
+    #[cfg(feature = "journal")]
+    fn hex_dump_annotated_with_journal() {
+        let mut storage = [0u64; 32];
+        let mut writer = Writer::from(&mut storage);
+        writer.enable_journal();
+        writer.write_u32(1);
+        writer.write_u32(2);
+
+        let journal = writer.journal();
+        let dump = writer.hex_dump().with_journal(&journal).to_string();
+        let lines: Vec<&str> = dump.lines().collect();
+
+        assert_eq!(3, lines.len());
+        assert_eq!("         ^ #0 write Uint32 value @0 x1 [1]", lines[1]);
+        assert_eq!("         ^ #1 write Uint32 value @4 x1 [2]", lines[2]);
+    }
+}
@@ -1,15 +1,63 @@
-use std::cell::Cell;
-use std::mem;
+use core::cell::Cell;
+use core::mem;
+
+mod decimal;
+mod half;
+mod header;
+mod journal;
+mod payload;
+mod quantized;
+mod section;
+#[cfg(feature = "serde")]
+pub mod serde;
+mod tags;
+mod time;
+
+pub use decimal::{Decimal, DecimalArray, MAX_SCALE as DECIMAL_MAX_SCALE};
+pub use half::{Bf16, F16};
+pub use header::{
+    Header, HeaderError, FLAG_TYPE_TAGS, HEADER_MAGIC, HEADER_SIZE, PROTOCOL_VERSION,
+};
+pub use journal::{HexDump, JournalOp};
+#[cfg(feature = "journal")]
+pub use journal::{Journal, JournalEntry, JOURNAL_CAPACITY, JOURNAL_PREVIEW_BYTES};
+pub use payload::PayloadError;
+pub use quantized::{quantize_into, Quantize, Quantized};
+pub use section::MAX_SECTION_DEPTH;
+pub use tags::{DisplayTag, TagKind, TypeTag, TYPE_TAGS_ENABLED};
+pub use time::{Date32, Duration, TimestampMillis};
 
 fn align_up(offset: u32, bytes: u8) -> u32 {
     let mask = (bytes - 1) as u32;
     (offset + mask) & !mask
 }
 
-fn get_storage_mut<T>(storage: &mut [u64]) -> &mut [T] {
-    let ptr = storage.as_mut_ptr() as *mut T;
-    let len = storage.len() * mem::size_of::<u64>() / mem::size_of::<T>();
-    unsafe { std::slice::from_raw_parts_mut(ptr, len) }
+/// Rounds half away from zero, like `f64::round`, which isn't available in `core`.
+fn round_f64(value: f64) -> f64 {
+    // Beyond 2^52 every f64 is already an integer
+    if value.is_nan() || value.abs() >= 4_503_599_627_370_496.0 {
+        return value;
+    }
+
+    let truncated = value as i64 as f64;
+    let fraction = value - truncated;
+    if fraction >= 0.5 {
+        truncated + 1.0
+    } else if fraction <= -0.5 {
+        truncated - 1.0
+    } else {
+        truncated
+    }
+}
+
+/// Reinterprets `len` u64 words starting at `ptr` as a slice of `T`.
+///
+/// # Safety
+///
+/// `ptr` must be valid for reads and writes of `len` u64 words for `'a`.
+unsafe fn get_storage_mut<'a, T>(ptr: *mut u64, len: usize) -> &'a mut [T] {
+    let len = len * mem::size_of::<u64>() / mem::size_of::<T>();
+    core::slice::from_raw_parts_mut(ptr as *mut T, len)
 }
 
 /// Macro to generate write methods for Writer.
@@ -33,9 +81,11 @@ macro_rules! impl_writer_methods {
             #[doc = ""]
             #[doc = "Panics if the channel buffer would overflow."]
             pub fn $method_suffix(&mut self, value: $type) {
+                self.channel.write_tag::<$type>(TagKind::Value);
                 let offset = self.channel.offset_for::<$type>();
                 self.channel.$field[offset as usize] = value;
                 self.channel.advance::<$type>(1);
+                self.channel.record::<$type>(JournalOp::Write, TagKind::Value, offset as usize, 1);
             }
         )*
     };
@@ -78,10 +128,12 @@ macro_rules! impl_writer_array_methods {
             #[doc = ""]
             #[doc = "Panics if the channel buffer would overflow."]
             pub fn $copy_elements(&mut self, arr: &[$type]) {
+                self.channel.write_tag::<$type>(TagKind::Elements);
                 let start = self.channel.offset_for::<$type>() as usize;
                 let end = start + arr.len();
                 self.channel.$field[start..end].copy_from_slice(arr);
                 self.channel.advance::<$type>(arr.len() as u32);
+                self.channel.record::<$type>(JournalOp::Write, TagKind::Elements, start, arr.len() as u32);
             }
 
             #[doc = concat!("Initializes space for a single `", stringify!($type), "` value in the channel.")]
@@ -100,8 +152,10 @@ macro_rules! impl_writer_array_methods {
             #[doc = ""]
             #[doc = "Panics if the channel buffer would overflow."]
             pub fn $init(&mut self) -> *mut $type {
+                self.channel.write_tag::<$type>(TagKind::Value);
                 let offset = self.channel.offset_for::<$type>();
                 self.channel.advance::<$type>(1);
+                self.channel.record::<$type>(JournalOp::Init, TagKind::Value, offset as usize, 1);
                 unsafe { self.channel.$field.as_mut_ptr().add(offset as usize) }
             }
 
@@ -139,8 +193,10 @@ macro_rules! impl_writer_array_methods {
             #[doc = ""]
             #[doc = "Panics if the channel buffer would overflow."]
             pub fn $init_elements(&mut self, length: u32) -> &mut [$type] {
+                self.channel.write_tag::<$type>(TagKind::Elements);
                 let start = self.channel.offset_for::<$type>() as usize;
                 self.channel.advance::<$type>(length);
+                self.channel.record::<$type>(JournalOp::Init, TagKind::Elements, start, length);
                 &mut self.channel.$field[start..start + length as usize]
             }
         )*
@@ -166,9 +222,11 @@ macro_rules! impl_reader_methods {
             #[doc = ""]
             #[doc = "Panics if the channel buffer would overflow."]
             pub fn $read_method(&self) -> $type {
+                self.channel.check_tag::<$type>(TagKind::Value);
                 let offset = self.channel.offset_for::<$type>();
                 let result = self.channel.$field[offset as usize];
                 self.channel.advance::<$type>(1);
+                self.channel.record::<$type>(JournalOp::Read, TagKind::Value, offset as usize, 1);
                 result
             }
 
@@ -202,8 +260,10 @@ macro_rules! impl_reader_methods {
             #[doc = ""]
             #[doc = "Panics if the channel buffer would overflow."]
             pub fn $read_elements(&self, length: u32) -> &[$type] {
+                self.channel.check_tag::<$type>(TagKind::Elements);
                 let start = self.channel.offset_for::<$type>() as usize;
                 self.channel.advance::<$type>(length);
+                self.channel.record::<$type>(JournalOp::Read, TagKind::Elements, start, length);
                 &self.channel.$field[start..start + length as usize]
             }
         )*
@@ -212,52 +272,78 @@ macro_rules! impl_reader_methods {
 
 struct Channel<'a> {
     offset: Cell<u32>,
+    start: u32,
+    end: u32,
+    limit: Cell<u32>,
+    // The storage every view below was created from, so sections can share it
+    storage_ptr: *mut u64,
+    storage_len: usize,
     storage_u8: &'a mut [u8],
+    storage_i8: &'a mut [i8],
+    storage_u16: &'a mut [u16],
     storage_u32: &'a mut [u32],
     storage_i32: &'a mut [i32],
+    storage_f16: &'a mut [F16],
+    storage_bf16: &'a mut [Bf16],
     storage_f32: &'a mut [f32],
     storage_f64: &'a mut [f64],
+    storage_i64: &'a mut [i64],
+    storage_date32: &'a mut [Date32],
+    storage_timestamp: &'a mut [TimestampMillis],
+    storage_duration: &'a mut [Duration],
+    #[cfg(feature = "journal")]
+    journal: core::cell::RefCell<Journal>,
 }
 
 impl<'a> Channel<'a> {
     fn from(storage: &'a mut [u64]) -> Self {
+        let storage_len = storage.len();
+        let end = mem::size_of_val(storage) as u32;
+
         // This is unsafe but necessary for the zero-allocation pattern
-        unsafe {
-            let storage_ptr = storage.as_mut_ptr();
-            let storage_len = storage.len();
-
-            Self {
-                offset: Cell::new(0),
-                storage_u8: get_storage_mut(std::slice::from_raw_parts_mut(
-                    storage_ptr,
-                    storage_len,
-                )),
-                storage_u32: get_storage_mut(std::slice::from_raw_parts_mut(
-                    storage_ptr,
-                    storage_len,
-                )),
-                storage_i32: get_storage_mut(std::slice::from_raw_parts_mut(
-                    storage_ptr,
-                    storage_len,
-                )),
-                storage_f32: get_storage_mut(std::slice::from_raw_parts_mut(
-                    storage_ptr,
-                    storage_len,
-                )),
-                storage_f64: get_storage_mut(std::slice::from_raw_parts_mut(
-                    storage_ptr,
-                    storage_len,
-                )),
-            }
+        unsafe { Self::from_raw(storage.as_mut_ptr(), storage_len, 0, end) }
+    }
+
+    /// Creates a channel over raw storage, restricted to the byte range `start..end`.
+    ///
+    /// # Safety
+    ///
+    /// `storage_ptr` must be valid for reads and writes of `storage_len` u64 words for `'a`.
+    unsafe fn from_raw(storage_ptr: *mut u64, storage_len: usize, start: u32, end: u32) -> Self {
+        Self {
+            offset: Cell::new(start),
+            start,
+            end,
+            limit: Cell::new(end),
+            storage_ptr,
+            storage_len,
+            storage_u8: get_storage_mut(storage_ptr, storage_len),
+            storage_i8: get_storage_mut(storage_ptr, storage_len),
+            storage_u16: get_storage_mut(storage_ptr, storage_len),
+            storage_u32: get_storage_mut(storage_ptr, storage_len),
+            storage_i32: get_storage_mut(storage_ptr, storage_len),
+            storage_f16: get_storage_mut(storage_ptr, storage_len),
+            storage_bf16: get_storage_mut(storage_ptr, storage_len),
+            storage_f32: get_storage_mut(storage_ptr, storage_len),
+            storage_f64: get_storage_mut(storage_ptr, storage_len),
+            storage_i64: get_storage_mut(storage_ptr, storage_len),
+            storage_date32: get_storage_mut(storage_ptr, storage_len),
+            storage_timestamp: get_storage_mut(storage_ptr, storage_len),
+            storage_duration: get_storage_mut(storage_ptr, storage_len),
+            #[cfg(feature = "journal")]
+            journal: core::cell::RefCell::new(Journal::new()),
         }
     }
 
     fn reset(&mut self) {
-        self.offset.set(0);
+        self.offset.set(self.start);
+        self.limit.set(self.end);
+        #[cfg(feature = "journal")]
+        self.journal.get_mut().clear();
     }
 
     fn check_offset(&self) {
-        if self.offset.get() > self.storage_u8.len() as u32 {
+        if self.offset.get() > self.limit.get() {
             panic!("Channel buffer overflow");
         }
     }
@@ -274,6 +360,7 @@ impl<'a> Channel<'a> {
         let offset = self.offset.get();
         match mem::size_of::<T>() {
             1 => offset,
+            2 => offset >> 1,
             4 => offset >> 2,
             8 => offset >> 3,
             _ => panic!("Invalid type size"),
@@ -286,6 +373,24 @@ impl<'a> Channel<'a> {
             .set(current + count * mem::size_of::<T>() as u32);
         self.check_offset();
     }
+
+    /// Writes a single aligned value without a type tag.
+    fn put<T: Copy>(&mut self, value: T) {
+        let index = self.offset_for::<T>() as usize;
+        self.advance::<T>(1);
+        unsafe {
+            (self.storage_u8.as_mut_ptr() as *mut T)
+                .add(index)
+                .write(value)
+        }
+    }
+
+    /// Reads a single aligned value without checking a type tag.
+    fn take<T: Copy>(&self) -> T {
+        let index = self.offset_for::<T>() as usize;
+        self.advance::<T>(1);
+        unsafe { (self.storage_u8.as_ptr() as *const T).add(index).read() }
+    }
 }
 
 /// A zero-allocation writer for the communication channel.
@@ -309,6 +414,8 @@ impl<'a> Channel<'a> {
 /// ```
 pub struct Writer<'a> {
     channel: Channel<'a>,
+    sections: [u32; MAX_SECTION_DEPTH],
+    section_depth: usize,
 }
 
 impl<'a> Writer<'a> {
@@ -327,14 +434,18 @@ impl<'a> Writer<'a> {
     pub fn from(storage: &'a mut [u64]) -> Self {
         Self {
             channel: Channel::from(storage),
+            sections: [0; MAX_SECTION_DEPTH],
+            section_depth: 0,
         }
     }
 
     /// Resets the writer to the beginning of the buffer.
     ///
     /// This allows reusing the same buffer for multiple write operations.
+    /// Any open sections are discarded.
     pub fn reset(&mut self) {
         self.channel.reset();
+        self.section_depth = 0;
     }
 
     /// Writes a `usize` value as a `u32` to the channel.
@@ -353,19 +464,35 @@ impl<'a> Writer<'a> {
     // Generate basic write methods using macro
     impl_writer_methods! {
         u8, storage_u8, write_u8;
+        i8, storage_i8, write_i8;
+        u16, storage_u16, write_u16;
         u32, storage_u32, write_u32;
         i32, storage_i32, write_i32;
+        F16, storage_f16, write_f16;
+        Bf16, storage_bf16, write_bf16;
         f32, storage_f32, write_f32;
-        f64, storage_f64, write_f64
+        f64, storage_f64, write_f64;
+        i64, storage_i64, write_i64;
+        Date32, storage_date32, write_date32;
+        TimestampMillis, storage_timestamp, write_timestamp_millis;
+        Duration, storage_duration, write_duration
     }
 
     // Generate array and init methods using macro
     impl_writer_array_methods! {
         u8, storage_u8, copy_array_u8, copy_elements_u8, init_u8, init_array_u8, init_elements_u8;
+        i8, storage_i8, copy_array_i8, copy_elements_i8, init_i8, init_array_i8, init_elements_i8;
+        u16, storage_u16, copy_array_u16, copy_elements_u16, init_u16, init_array_u16, init_elements_u16;
         u32, storage_u32, copy_array_u32, copy_elements_u32, init_u32, init_array_u32, init_elements_u32;
         i32, storage_i32, copy_array_i32, copy_elements_i32, init_i32, init_array_i32, init_elements_i32;
+        F16, storage_f16, copy_array_f16, copy_elements_f16, init_f16, init_array_f16, init_elements_f16;
+        Bf16, storage_bf16, copy_array_bf16, copy_elements_bf16, init_bf16, init_array_bf16, init_elements_bf16;
         f32, storage_f32, copy_array_f32, copy_elements_f32, init_f32, init_array_f32, init_elements_f32;
-        f64, storage_f64, copy_array_f64, copy_elements_f64, init_f64, init_array_f64, init_elements_f64
+        f64, storage_f64, copy_array_f64, copy_elements_f64, init_f64, init_array_f64, init_elements_f64;
+        i64, storage_i64, copy_array_i64, copy_elements_i64, init_i64, init_array_i64, init_elements_i64;
+        Date32, storage_date32, copy_array_date32, copy_elements_date32, init_date32, init_array_date32, init_elements_date32;
+        TimestampMillis, storage_timestamp, copy_array_timestamp_millis, copy_elements_timestamp_millis, init_timestamp_millis, init_array_timestamp_millis, init_elements_timestamp_millis;
+        Duration, storage_duration, copy_array_duration, copy_elements_duration, init_duration, init_array_duration, init_elements_duration
     }
 }
 
@@ -378,8 +505,14 @@ impl<'a> Writer<'a> {
 /// # Examples
 ///
 /// ```rust
-/// # use zaw::conduit::Reader;
+/// # use zaw::conduit::{Reader, Writer};
 /// let mut storage = vec![0u64; 1024];
+/// # {
+/// #     let mut writer = Writer::from(&mut storage);
+/// #     writer.write_u32(42);
+/// #     writer.write_f64(3.14159);
+/// #     writer.copy_array_i32(&[1, 2, 3, 4]);
+/// # }
 /// let mut reader = Reader::from(&mut storage);
 ///
 /// let value = reader.read_u32();
@@ -389,6 +522,7 @@ impl<'a> Writer<'a> {
 /// ```
 pub struct Reader<'a> {
     channel: Channel<'a>,
+    strict: bool,
 }
 
 impl<'a> Reader<'a> {
@@ -407,6 +541,7 @@ impl<'a> Reader<'a> {
     pub fn from(storage: &'a mut [u64]) -> Self {
         Self {
             channel: Channel::from(storage),
+            strict: false,
         }
     }
 
@@ -420,10 +555,18 @@ impl<'a> Reader<'a> {
     // Generate all read methods using macro
     impl_reader_methods! {
         u8, storage_u8, read_u8, read_array_u8, read_elements_u8;
+        i8, storage_i8, read_i8, read_array_i8, read_elements_i8;
+        u16, storage_u16, read_u16, read_array_u16, read_elements_u16;
         u32, storage_u32, read_u32, read_array_u32, read_elements_u32;
         i32, storage_i32, read_i32, read_array_i32, read_elements_i32;
+        F16, storage_f16, read_f16, read_array_f16, read_elements_f16;
+        Bf16, storage_bf16, read_bf16, read_array_bf16, read_elements_bf16;
         f32, storage_f32, read_f32, read_array_f32, read_elements_f32;
-        f64, storage_f64, read_f64, read_array_f64, read_elements_f64
+        f64, storage_f64, read_f64, read_array_f64, read_elements_f64;
+        i64, storage_i64, read_i64, read_array_i64, read_elements_i64;
+        Date32, storage_date32, read_date32, read_array_date32, read_elements_date32;
+        TimestampMillis, storage_timestamp, read_timestamp_millis, read_array_timestamp_millis, read_elements_timestamp_millis;
+        Duration, storage_duration, read_duration, read_array_duration, read_elements_duration
     }
 }
 
@@ -0,0 +1,142 @@
+use core::fmt;
+
+use super::{Reader, Writer};
+
+/// An invalid payload length, or a payload that wasn't fully read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PayloadError {
+    /// The payload length is larger than the channel.
+    TooLarge { length: u32, capacity: u32 },
+    /// A strict reader finished with unread bytes left in the payload.
+    TrailingBytes { offset: u32, remaining: u32 },
+}
+
+impl fmt::Display for PayloadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            PayloadError::TooLarge { length, capacity } => write!(
+                f,
+                "Payload length {} exceeds channel capacity {}",
+                length, capacity
+            ),
+            PayloadError::TrailingBytes { offset, remaining } => write!(
+                f,
+                "Payload has {} unread trailing bytes at offset {}",
+                remaining, offset
+            ),
+        }
+    }
+}
+
+impl core::error::Error for PayloadError {}
+
+impl<'a> Writer<'a> {
+    /// Returns the number of bytes written since the last reset.
+    ///
+    /// This is the payload length a reader should be bounded to.
+    pub fn length(&self) -> u32 {
+        self.channel.offset.get() - self.channel.start
+    }
+
+    /// Fills the unwritten remainder of the channel with `byte`.
+    ///
+    /// Debug builds use this so that reading bytes that were never written
+    /// yields an obvious pattern rather than plausible stale values.
+    pub fn fill_remaining(&mut self, byte: u8) {
+        let offset = self.channel.offset.get() as usize;
+        let end = self.channel.end as usize;
+        self.channel.storage_u8[offset..end].fill(byte);
+    }
+}
+
+impl<'a> Reader<'a> {
+    /// Bounds the reader to the number of bytes the writer actually wrote.
+    ///
+    /// Reading beyond the payload panics, trapping a WASM module, instead of
+    /// returning stale data left over from a previous message. This happens
+    /// whether or not the reader is strict. The bound is cleared by
+    /// [`Reader::reset`].
+    ///
+    /// # Arguments
+    ///
+    /// * `length` - The payload length in bytes, from the start of the channel
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PayloadError::TooLarge`] if the length exceeds the channel.
+    pub fn set_payload_length(&mut self, length: u32) -> Result<(), PayloadError> {
+        let capacity = self.channel.end - self.channel.start;
+        if length > capacity {
+            return Err(PayloadError::TooLarge { length, capacity });
+        }
+
+        self.channel.limit.set(self.channel.start + length);
+
+        Ok(())
+    }
+
+    /// Returns the payload length in bytes, or the channel capacity if unknown.
+    pub fn payload_length(&self) -> u32 {
+        self.channel.limit.get() - self.channel.start
+    }
+
+    /// Returns the number of unread bytes before the end of the payload.
+    pub fn remaining(&self) -> u32 {
+        self.channel
+            .limit
+            .get()
+            .saturating_sub(self.channel.offset.get())
+    }
+
+    /// Returns `true` if there are unread bytes before the end of the payload.
+    pub fn has_remaining(&self) -> bool {
+        self.remaining() > 0
+    }
+
+    /// Returns `true` if the whole payload has been read.
+    pub fn is_exhausted(&self) -> bool {
+        !self.has_remaining()
+    }
+
+    /// Enables or disables strict mode, which persists across resets.
+    ///
+    /// In strict mode [`Reader::finish`] reports unread trailing bytes as an
+    /// error, catching a writer that sent more than the reader expected. It
+    /// is only meaningful once the payload length is known, either from
+    /// [`Reader::set_payload_length`] or a message header.
+    ///
+    /// Strict mode doesn't change reads: one past the end of the payload
+    /// panics either way, so a writer that sent too little still traps.
+    ///
+    /// Nothing calls [`Reader::finish`] automatically, including the interop
+    /// layer's `handle`, so exports using strict mode call it themselves once
+    /// they're done reading.
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    /// Returns `true` if the reader is in strict mode.
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// Checks that the payload was fully read, if the reader is in strict mode.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PayloadError::TrailingBytes`] if the reader is strict and
+    /// bytes remain before the end of the payload.
+    pub fn finish(&self) -> Result<(), PayloadError> {
+        if self.strict && self.has_remaining() {
+            return Err(PayloadError::TrailingBytes {
+                offset: self.channel.offset.get() - self.channel.start,
+                remaining: self.remaining(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test;
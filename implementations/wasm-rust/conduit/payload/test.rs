@@ -0,0 +1,115 @@
+#[cfg_attr(feature = "type-tags", allow(unused_imports))]
+use super::super::{Reader, Writer};
+use super::PayloadError;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(not(feature = "type-tags"))]
+    #[test]
+    fn remaining_tracks_payload_length() {
+        let mut storage = [0u64; 32];
+        {
+            let mut writer = Writer::from(&mut storage);
+            writer.write_u32(1);
+            writer.write_u8(2);
+        }
+
+        let mut reader = Reader::from(&mut storage);
+        reader.set_payload_length(5).unwrap();
+
+        assert_eq!(5, reader.payload_length());
+        assert_eq!(5, reader.remaining());
+        assert!(reader.has_remaining());
+
+        assert_eq!(1, reader.read_u32());
+        assert_eq!(1, reader.remaining());
+
+        assert_eq!(2, reader.read_u8());
+        assert!(reader.is_exhausted());
+        assert!(reader.finish().is_ok());
+    }
+
+    #[cfg(not(feature = "type-tags"))]
+    #[test]
+    #[should_panic(expected = "Channel buffer overflow")]
+    fn read_past_payload_panics() {
+        let mut storage = [0u64; 32];
+        let mut reader = Reader::from(&mut storage);
+        reader.set_payload_length(4).unwrap();
+
+        reader.read_u32();
+        reader.read_u32();
+    }
+
+    #[test]
+    fn payload_length_larger_than_channel() {
+        let mut storage = [0u64; 4];
+        let mut reader = Reader::from(&mut storage);
+
+        assert_eq!(
+            Err(PayloadError::TooLarge {
+                length: 40,
+                capacity: 32
+            }),
+            reader.set_payload_length(40)
+        );
+    }
+
+    #[cfg(not(feature = "type-tags"))]
+    #[test]
+    fn strict_mode_rejects_trailing_bytes() {
+        let mut storage = [0u64; 32];
+        let mut reader = Reader::from(&mut storage);
+        reader.set_payload_length(8).unwrap();
+        reader.read_u32();
+
+        assert!(reader.finish().is_ok());
+
+        reader.set_strict(true);
+        assert_eq!(
+            Err(PayloadError::TrailingBytes {
+                offset: 4,
+                remaining: 4
+            }),
+            reader.finish()
+        );
+    }
+
+    #[test]
+    fn reset_clears_length_but_keeps_strict() {
+        let mut storage = [0u64; 4];
+        let mut reader = Reader::from(&mut storage);
+        reader.set_strict(true);
+        reader.set_payload_length(4).unwrap();
+
+        reader.reset();
+
+        assert_eq!(32, reader.payload_length());
+        assert!(reader.is_strict());
+    }
+
+    #[cfg(not(feature = "type-tags"))]
+    #[test]
+    fn writer_length_and_fill_remaining() {
+        let mut storage = [0u64; 2];
+        {
+            let mut writer = Writer::from(&mut storage);
+            writer.write_u8(1);
+            writer.write_u16(2);
+            assert_eq!(4, writer.length());
+
+            writer.fill_remaining(0xcd);
+
+            writer.reset();
+            assert_eq!(0, writer.length());
+        }
+
+        let expected = [
+            1, 0, 2, 0, 0xcd, 0xcd, 0xcd, 0xcd, 0xcd, 0xcd, 0xcd, 0xcd, 0xcd, 0xcd, 0xcd, 0xcd,
+        ];
+        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 16) };
+        assert_eq!(&expected[..], actual);
+    }
+}
@@ -0,0 +1,172 @@
+use super::{round_f64, Reader, Writer};
+
+/// An integer type that can hold affine-quantized values.
+///
+/// Implemented for `i8` and `u8`. A quantized value `q` represents the real
+/// value `(q - zero_point) * scale`.
+pub trait Quantize: Copy {
+    /// Converts a rounded, zero-point-adjusted value to `Self`, saturating at the type bounds.
+    fn from_i32_saturating(value: i32) -> Self;
+
+    /// Widens this value to an `i32`.
+    fn to_i32(self) -> i32;
+}
+
+impl Quantize for i8 {
+    fn from_i32_saturating(value: i32) -> Self {
+        value.clamp(i8::MIN as i32, i8::MAX as i32) as i8
+    }
+
+    fn to_i32(self) -> i32 {
+        self as i32
+    }
+}
+
+impl Quantize for u8 {
+    fn from_i32_saturating(value: i32) -> Self {
+        value.clamp(u8::MIN as i32, u8::MAX as i32) as u8
+    }
+
+    fn to_i32(self) -> i32 {
+        self as i32
+    }
+}
+
+/// A quantized array read from the channel.
+///
+/// Each element `q` represents the real value `(q - zero_point) * scale`.
+///
+/// The wire encoding is a `Float32` scale, an `Int32` zero point and then the
+/// values as a length-prefixed `Int8[]` or `Uint8[]` array.
+#[derive(Debug, Clone, Copy)]
+pub struct Quantized<'a, T> {
+    pub scale: f32,
+    pub zero_point: i32,
+    pub values: &'a [T],
+}
+
+impl<'a, T: Quantize> Quantized<'a, T> {
+    /// Returns the number of quantized values.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns `true` if there are no quantized values.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns the real value of the element at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn dequantize(&self, index: usize) -> f32 {
+        dequantize_value(self.values[index], self.scale, self.zero_point)
+    }
+
+    /// Writes the real value of every element into `dst`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `dst` has a different length to the quantized values.
+    pub fn dequantize_into(&self, dst: &mut [f32]) {
+        assert_eq!(self.values.len(), dst.len(), "Slice length mismatch");
+        for (out, &value) in dst.iter_mut().zip(self.values) {
+            *out = dequantize_value(value, self.scale, self.zero_point);
+        }
+    }
+}
+
+fn dequantize_value<T: Quantize>(value: T, scale: f32, zero_point: i32) -> f32 {
+    (value.to_i32() - zero_point) as f32 * scale
+}
+
+/// Quantizes each `f32` in `src` into the corresponding slot of `dst`.
+///
+/// Values are rounded to the nearest integer and saturate at the bounds of `T`.
+///
+/// # Panics
+///
+/// Panics if `src` and `dst` have different lengths.
+pub fn quantize_into<T: Quantize>(src: &[f32], scale: f32, zero_point: i32, dst: &mut [T]) {
+    assert_eq!(src.len(), dst.len(), "Slice length mismatch");
+    let inverse_scale = 1.0 / scale;
+    for (out, &value) in dst.iter_mut().zip(src) {
+        let scaled = round_f64((value * inverse_scale) as f64) as i32;
+        *out = T::from_i32_saturating(scaled.saturating_add(zero_point));
+    }
+}
+
+/// Macro to generate quantized array methods for Writer and Reader.
+macro_rules! impl_quantized_methods {
+    ($($type:ty, $copy:ident, $init:ident, $copy_array:ident, $init_array:ident, $read:ident, $read_array:ident);*) => {
+        impl<'a> Writer<'a> {
+            $(
+                #[doc = concat!("Copies a quantized `", stringify!($type), "` array to the channel.")]
+                #[doc = ""]
+                #[doc = "Writes the scale as f32, the zero point as i32 and then the values with length prefix."]
+                #[doc = ""]
+                #[doc = "# Arguments"]
+                #[doc = ""]
+                #[doc = "* `scale` - The real value of one quantization step"]
+                #[doc = "* `zero_point` - The quantized value that represents real zero"]
+                #[doc = concat!("* `values` - The `", stringify!($type), "` slice to copy")]
+                #[doc = ""]
+                #[doc = "# Panics"]
+                #[doc = ""]
+                #[doc = "Panics if the channel buffer would overflow."]
+                pub fn $copy(&mut self, scale: f32, zero_point: i32, values: &[$type]) {
+                    self.write_f32(scale);
+                    self.write_i32(zero_point);
+                    self.$copy_array(values);
+                }
+
+                #[doc = concat!("Initializes space for a quantized `", stringify!($type), "` array.")]
+                #[doc = ""]
+                #[doc = "Writes the scale, zero point and length prefix, then initializes space for the values."]
+                #[doc = ""]
+                #[doc = "# Returns"]
+                #[doc = ""]
+                #[doc = concat!("A mutable slice of `", stringify!($type), "` values.")]
+                #[doc = ""]
+                #[doc = "# Panics"]
+                #[doc = ""]
+                #[doc = "Panics if the channel buffer would overflow."]
+                pub fn $init(&mut self, scale: f32, zero_point: i32, length: u32) -> &mut [$type] {
+                    self.write_f32(scale);
+                    self.write_i32(zero_point);
+                    self.$init_array(length)
+                }
+            )*
+        }
+
+        impl<'a> Reader<'a> {
+            $(
+                #[doc = concat!("Reads a quantized `", stringify!($type), "` array from the channel.")]
+                #[doc = ""]
+                #[doc = "# Returns"]
+                #[doc = ""]
+                #[doc = "The scale, zero point and a slice of the raw quantized values."]
+                #[doc = ""]
+                #[doc = "# Panics"]
+                #[doc = ""]
+                #[doc = "Panics if the channel buffer would overflow."]
+                pub fn $read(&self) -> Quantized<'_, $type> {
+                    let scale = self.read_f32();
+                    let zero_point = self.read_i32();
+                    let values = self.$read_array();
+                    Quantized { scale, zero_point, values }
+                }
+            )*
+        }
+    };
+}
+
+impl_quantized_methods! {
+    i8, copy_quantized_i8, init_quantized_i8, copy_array_i8, init_array_i8, read_quantized_i8, read_array_i8;
+    u8, copy_quantized_u8, init_quantized_u8, copy_array_u8, init_array_u8, read_quantized_u8, read_array_u8
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,62 @@
+use super::super::{Reader, Writer};
+use super::quantize_into;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(not(feature = "type-tags"))]
+    #[test]
+    fn quantized_i8_array() {
+        let mut storage = [0u64; 32];
+        {
+            let mut writer = Writer::from(&mut storage);
+            writer.copy_quantized_i8(0.5, -1, &[-1, 1, 127]);
+        }
+
+        let expected = [
+            0, 0, 0, 63, 255, 255, 255, 255, 3, 0, 0, 0, 255, 1, 127, 0u8,
+        ];
+        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 16) };
+        assert_eq!(&expected[..], actual);
+
+        let mut reader = Reader::from(&mut storage);
+        reader.reset();
+
+        let quantized = reader.read_quantized_i8();
+        assert_eq!(0.5, quantized.scale);
+        assert_eq!(-1, quantized.zero_point);
+        assert_eq!(&[-1, 1, 127], quantized.values);
+        assert_eq!(0.0, quantized.dequantize(0));
+        assert_eq!(64.0, quantized.dequantize(2));
+    }
+
+    #[test]
+    fn quantized_u8_init_and_dequantize() {
+        let mut storage = [0u64; 32];
+        {
+            let mut writer = Writer::from(&mut storage);
+            writer.write_u8(9);
+            let values = writer.init_quantized_u8(0.25, 128, 4);
+            quantize_into(&[-32.0, 0.0, 0.3, 100.0], 0.25, 128, values);
+        }
+
+        let mut reader = Reader::from(&mut storage);
+        reader.reset();
+
+        assert_eq!(9, reader.read_u8());
+        let quantized = reader.read_quantized_u8();
+        assert_eq!(&[0, 128, 129, 255], quantized.values);
+
+        let mut dequantized = [0f32; 4];
+        quantized.dequantize_into(&mut dequantized);
+        assert_eq!([-32.0, 0.0, 0.25, 31.75], dequantized);
+    }
+
+    #[test]
+    fn quantize_saturates() {
+        let mut values = [0i8; 3];
+        quantize_into(&[-1000.0, 0.6, 1000.0], 1.0, 0, &mut values);
+        assert_eq!([-128, 1, 127], values);
+    }
+}
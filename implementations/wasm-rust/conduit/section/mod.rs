@@ -0,0 +1,107 @@
+use super::{Channel, JournalOp, Reader, TagKind, Writer};
+
+/// The maximum number of sections a `Writer` can have open at once.
+pub const MAX_SECTION_DEPTH: usize = 16;
+
+impl<'a> Writer<'a> {
+    /// Begins a length-delimited section.
+    ///
+    /// Writes a placeholder `u32` byte length which is filled in by the matching
+    /// [`Writer::end_section`]. Sections may be nested up to [`MAX_SECTION_DEPTH`]
+    /// levels deep, and readers can skip a whole section without decoding it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the channel buffer would overflow or too many sections are open.
+    pub fn begin_section(&mut self) {
+        if self.section_depth == MAX_SECTION_DEPTH {
+            panic!("Section nesting exceeds {} levels", MAX_SECTION_DEPTH);
+        }
+
+        self.write_u32(0);
+        self.sections[self.section_depth] = self.channel.offset.get();
+        self.section_depth += 1;
+    }
+
+    /// Ends the most recently opened section, writing its byte length.
+    ///
+    /// # Returns
+    ///
+    /// The length of the section in bytes, excluding its length prefix.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no open section.
+    pub fn end_section(&mut self) -> u32 {
+        if self.section_depth == 0 {
+            panic!("No open section to end");
+        }
+
+        self.section_depth -= 1;
+        let start = self.sections[self.section_depth];
+        let length = self.channel.offset.get() - start;
+
+        // The length prefix is the u32 immediately before the section contents
+        self.channel.storage_u32[(start >> 2) as usize - 1] = length;
+
+        length
+    }
+}
+
+impl<'a> Reader<'a> {
+    /// Reads a length-delimited section written by [`Writer::begin_section`].
+    ///
+    /// The parent reader advances past the whole section. The returned reader is
+    /// bounded to the section, so reading beyond its end panics rather than
+    /// walking into the rest of the message. Calling `reset` on the returned
+    /// reader rewinds it to the start of the section.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the channel buffer would overflow.
+    pub fn read_section(&self) -> Reader<'_> {
+        let length = self.read_u32();
+        let start = self.channel.offset.get();
+        self.channel.advance::<u8>(length);
+        self.channel.record::<u8>(
+            JournalOp::Advance,
+            TagKind::Elements,
+            start as usize,
+            length,
+        );
+
+        let storage_ptr = self.channel.storage_ptr;
+        let storage_len = self.channel.storage_len;
+
+        // Readers never write to their storage, so sharing it with the section is sound
+        Reader {
+            channel: unsafe { Channel::from_raw(storage_ptr, storage_len, start, start + length) },
+            strict: false,
+        }
+    }
+
+    /// Skips over a length-delimited section without decoding it.
+    ///
+    /// # Returns
+    ///
+    /// The length of the skipped section in bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the channel buffer would overflow.
+    pub fn skip_section(&self) -> u32 {
+        let length = self.read_u32();
+        let start = self.channel.offset.get();
+        self.channel.advance::<u8>(length);
+        self.channel.record::<u8>(
+            JournalOp::Advance,
+            TagKind::Elements,
+            start as usize,
+            length,
+        );
+        length
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,138 @@
+use super::super::{Reader, Writer};
+use super::MAX_SECTION_DEPTH;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(not(feature = "type-tags"))]
+    #[test]
+    fn section_with_length_prefix() {
+        let mut storage = [0u64; 32];
+        {
+            let mut writer = Writer::from(&mut storage);
+            writer.write_u8(1);
+            writer.begin_section();
+            writer.write_u8(2);
+            writer.write_u32(3);
+            assert_eq!(8, writer.end_section());
+            writer.write_u8(4);
+        }
+
+        let expected = [1, 0, 0, 0, 8, 0, 0, 0, 2, 0, 0, 0, 3, 0, 0, 0, 4, 0, 0, 0u8];
+        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 20) };
+        assert_eq!(&expected[..], actual);
+
+        let mut reader = Reader::from(&mut storage);
+        reader.reset();
+
+        assert_eq!(1, reader.read_u8());
+        {
+            let section = reader.read_section();
+            assert_eq!(2, section.read_u8());
+            assert_eq!(3, section.read_u32());
+        }
+        assert_eq!(4, reader.read_u8());
+    }
+
+    #[test]
+    fn nested_sections_keep_alignment() {
+        let mut storage = [0u64; 32];
+        {
+            let mut writer = Writer::from(&mut storage);
+            writer.begin_section();
+            writer.write_u8(1);
+            writer.begin_section();
+            writer.write_f64(2.5);
+            writer.end_section();
+            writer.copy_array_i32(&[-1, -2]);
+            writer.end_section();
+            writer.write_u32(99);
+        }
+
+        let mut reader = Reader::from(&mut storage);
+        reader.reset();
+
+        let outer = reader.read_section();
+        assert_eq!(1, outer.read_u8());
+        let inner = outer.read_section();
+        assert_eq!(2.5, inner.read_f64());
+        assert_eq!(&[-1, -2], outer.read_array_i32());
+        assert_eq!(99, reader.read_u32());
+    }
+
+    #[cfg(not(feature = "type-tags"))]
+    #[test]
+    fn sections_can_be_skipped_and_reset() {
+        let mut storage = [0u64; 32];
+        {
+            let mut writer = Writer::from(&mut storage);
+            writer.begin_section();
+            writer.copy_array_f32(&[1.0, 2.0, 3.0]);
+            writer.end_section();
+            writer.begin_section();
+            writer.write_u32(7);
+            writer.end_section();
+        }
+
+        let mut reader = Reader::from(&mut storage);
+        reader.reset();
+
+        assert_eq!(16, reader.skip_section());
+        let mut section = reader.read_section();
+        assert_eq!(7, section.read_u32());
+        section.reset();
+        assert_eq!(7, section.read_u32());
+    }
+
+    #[test]
+    #[should_panic(expected = "Channel buffer overflow")]
+    fn reading_past_section_end_panics() {
+        let mut storage = [0u64; 32];
+        {
+            let mut writer = Writer::from(&mut storage);
+            writer.begin_section();
+            writer.write_u32(1);
+            writer.end_section();
+            writer.write_u32(2);
+        }
+
+        let reader = Reader::from(&mut storage);
+        let section = reader.read_section();
+        section.read_u32();
+        section.read_u32();
+    }
+
+    #[test]
+    #[should_panic(expected = "Channel buffer overflow")]
+    fn section_length_beyond_buffer_panics() {
+        let mut storage = [0u64; 2];
+        {
+            let mut writer = Writer::from(&mut storage);
+            writer.write_u32(1000);
+        }
+
+        let reader = Reader::from(&mut storage);
+        reader.read_section();
+    }
+
+    #[test]
+    #[should_panic(expected = "No open section to end")]
+    fn end_without_begin_panics() {
+        let mut storage = [0u64; 4];
+        let mut writer = Writer::from(&mut storage);
+        writer.end_section();
+    }
+
+    #[test]
+    fn reset_discards_open_sections() {
+        let mut storage = [0u64; 32];
+        let mut writer = Writer::from(&mut storage);
+        for _ in 0..MAX_SECTION_DEPTH {
+            writer.begin_section();
+        }
+        writer.reset();
+        writer.begin_section();
+        assert_eq!(0, writer.end_section());
+    }
+}
@@ -0,0 +1,126 @@
+//! Serde support for conduit channels (requires the `serde` feature).
+//!
+//! Values are encoded with the existing aligned primitive encodings, in field
+//! order and without any type information, so both sides must agree on the
+//! schema. This is intended for configuration and small metadata objects; hot
+//! paths should keep using the typed `Writer`/`Reader` methods directly.
+//!
+//! | Serde type                   | Encoding                                         |
+//! | ---------------------------- | ------------------------------------------------ |
+//! | `bool`                       | `Uint8` (0 or 1)                                 |
+//! | `u8` / `i8`                  | `Uint8` / `Int8`                                 |
+//! | `u16`, `u32` / `i16`, `i32`  | `Uint32` / `Int32`                               |
+//! | `u64` / `i64`                | `Int64` (`u64` is bit-cast)                      |
+//! | `f32` / `f64`                | `Float32` / `Float64`                            |
+//! | `char`                       | `Uint32`                                         |
+//! | `str` / bytes                | `Uint8[]`                                        |
+//! | `Option`                     | `Uint8` tag (0 or 1), then the value if present  |
+//! | unit, unit struct            | nothing                                          |
+//! | newtype struct               | the inner value                                  |
+//! | sequence                     | `Uint32` length, then the elements               |
+//! | tuple, tuple struct, struct  | the fields in order                              |
+//! | map                          | `Uint32` length, then key/value pairs            |
+//! | enum                         | `Uint32` variant index, then the variant fields  |
+//!
+//! Deserialization borrows `&str` and `&[u8]` values directly from the channel.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # use zaw::conduit::{Reader, Writer};
+//! #[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug)]
+//! struct Config<'a> {
+//!     name: &'a str,
+//!     threshold: f64,
+//!     bins: Vec<u32>,
+//! }
+//!
+//! let mut storage = vec![0u64; 64];
+//! let config = Config { name: "sizes", threshold: 0.5, bins: vec![1, 2, 3] };
+//!
+//! Writer::from(&mut storage).write_serde(&config).unwrap();
+//!
+//! let mut reader = Reader::from(&mut storage);
+//! let decoded: Config = reader.read_serde().unwrap();
+//! assert_eq!(config, decoded);
+//! ```
+
+use core::fmt;
+
+use super::{Reader, Writer};
+
+mod de;
+mod ser;
+
+/// An error raised while serializing or deserializing over a channel.
+#[derive(Debug)]
+pub struct Error {
+    message: String,
+}
+
+impl Error {
+    fn new(message: impl fmt::Display) -> Self {
+        Self {
+            message: message.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl core::error::Error for Error {}
+
+impl ::serde::ser::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self::new(msg)
+    }
+}
+
+impl ::serde::de::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self::new(msg)
+    }
+}
+
+pub type Result<T> = core::result::Result<T, Error>;
+
+impl<'a> Writer<'a> {
+    /// Serializes `value` to the channel.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value contains an unsupported type (e.g. a
+    /// sequence of unknown length or a 128-bit integer).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the channel buffer would overflow.
+    pub fn write_serde<T: ::serde::Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        value.serialize(self)
+    }
+}
+
+impl<'de> Reader<'de> {
+    /// Deserializes a `T` from the channel.
+    ///
+    /// Borrowed `&str` and `&[u8]` fields point directly into the channel storage.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data does not match `T` (e.g. invalid UTF-8 or
+    /// an unknown enum variant).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the channel buffer would overflow.
+    pub fn read_serde<T: ::serde::Deserialize<'de>>(&mut self) -> Result<T> {
+        T::deserialize(self)
+    }
+}
+
+#[cfg(test)]
+mod test;
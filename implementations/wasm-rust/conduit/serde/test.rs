@@ -0,0 +1,142 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::super::{Reader, Writer};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    enum Mode {
+        Off,
+        Fixed(u16),
+        Range { min: i16, max: i16 },
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Config<'a> {
+        enabled: bool,
+        name: &'a str,
+        #[serde(with = "serde_bytes_borrowed")]
+        raw: &'a [u8],
+        scale: f32,
+        total: u64,
+        label: Option<char>,
+        modes: Vec<Mode>,
+        weights: BTreeMap<u32, f64>,
+        pair: (i8, String),
+    }
+
+    mod serde_bytes_borrowed {
+        use serde::{Deserialize, Deserializer, Serializer};
+
+        pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_bytes(bytes)
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<&'de [u8], D::Error> {
+            <&[u8]>::deserialize(deserializer)
+        }
+    }
+
+    #[test]
+    fn primitive_encodings_match_typed_methods() {
+        let mut storage = [0u64; 32];
+        {
+            let mut writer = Writer::from(&mut storage);
+            writer.write_serde(&(true, 7u16, -1i64, "hi")).unwrap();
+        }
+
+        // Type tags shift every value, so tagged builds only check the round trip
+        #[cfg(not(feature = "type-tags"))]
+        {
+            let expected = [
+                1, 0, 0, 0, 7, 0, 0, 0, 255, 255, 255, 255, 255, 255, 255, 255, 2, 0, 0, 0, 104,
+                105, 0, 0u8,
+            ];
+            let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 24) };
+            assert_eq!(&expected[..], actual);
+        }
+
+        let mut reader = Reader::from(&mut storage);
+        reader.reset();
+
+        assert_eq!(1, reader.read_u8());
+        assert_eq!(7, reader.read_u32());
+        assert_eq!(-1, reader.read_i64());
+        assert_eq!(b"hi", reader.read_array_u8());
+    }
+
+    #[test]
+    fn struct_round_trip_borrows_from_channel() {
+        let mut weights = BTreeMap::new();
+        weights.insert(3, 0.25);
+        weights.insert(9, -1.5);
+
+        let raw = [1u8, 2, 3];
+        let config = Config {
+            enabled: true,
+            name: "size curve",
+            raw: &raw,
+            scale: 1.5,
+            total: u64::MAX,
+            label: Some('λ'),
+            modes: vec![Mode::Off, Mode::Fixed(12), Mode::Range { min: -3, max: 4 }],
+            weights,
+            pair: (-8, String::from("pair")),
+        };
+
+        let mut storage = [0u64; 64];
+        {
+            let mut writer = Writer::from(&mut storage);
+            writer.write_serde(&config).unwrap();
+            writer.write_u32(0xdeadbeef);
+        }
+
+        let storage_range = storage.as_ptr_range();
+        let mut reader = Reader::from(&mut storage);
+        reader.reset();
+
+        let decoded: Config = reader.read_serde().unwrap();
+        assert_eq!(config, decoded);
+        assert!(storage_range.contains(&(decoded.name.as_ptr() as *const u64)));
+        assert_eq!(0xdeadbeef, reader.read_u32());
+    }
+
+    #[test]
+    fn invalid_data_returns_errors() {
+        let mut storage = [0u64; 32];
+        {
+            let mut writer = Writer::from(&mut storage);
+            writer.write_u8(2);
+            writer.copy_array_u8(&[0xff, 0xfe]);
+            writer.write_u32(7);
+        }
+
+        let mut reader = Reader::from(&mut storage);
+        reader.reset();
+
+        assert!(reader.read_serde::<bool>().is_err());
+        assert!(reader.read_serde::<&str>().is_err());
+        assert!(reader.read_serde::<Mode>().is_err());
+    }
+
+    #[test]
+    fn unknown_length_sequences_are_rejected() {
+        struct Unsized;
+
+        impl Serialize for Unsized {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_seq((0..3u32).filter(|_| true))
+            }
+        }
+
+        let mut storage = [0u64; 32];
+        let mut writer = Writer::from(&mut storage);
+        assert!(writer.write_serde(&Unsized).is_err());
+    }
+}
@@ -0,0 +1,180 @@
+use core::fmt;
+
+use super::{Bf16, Channel, Date32, Duration, TimestampMillis, F16};
+use crate::interop::error::ErrorCode;
+use crate::interop::panic::set_panic_code;
+
+/// Whether this build prefixes every value with a type tag (the `type-tags` feature).
+pub const TYPE_TAGS_ENABLED: bool = cfg!(feature = "type-tags");
+
+/// The element type recorded in a type tag.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypeTag {
+    Uint8 = 1,
+    Int8 = 2,
+    Uint16 = 3,
+    Uint32 = 4,
+    Int32 = 5,
+    Int64 = 6,
+    Float16 = 7,
+    BFloat16 = 8,
+    Float32 = 9,
+    Float64 = 10,
+    Date32 = 11,
+    TimestampMillis = 12,
+    Duration = 13,
+}
+
+/// Whether a type tag precedes a single value or a run of elements.
+///
+/// Arrays are tagged as a `Uint32` length value followed by elements.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TagKind {
+    Value = 0,
+    Elements = 1,
+}
+
+const TYPE_TAGS: [TypeTag; 13] = [
+    TypeTag::Uint8,
+    TypeTag::Int8,
+    TypeTag::Uint16,
+    TypeTag::Uint32,
+    TypeTag::Int32,
+    TypeTag::Int64,
+    TypeTag::Float16,
+    TypeTag::BFloat16,
+    TypeTag::Float32,
+    TypeTag::Float64,
+    TypeTag::Date32,
+    TypeTag::TimestampMillis,
+    TypeTag::Duration,
+];
+
+impl TypeTag {
+    /// Returns the protocol name of this type, e.g. `Float64`.
+    pub fn name(self) -> &'static str {
+        match self {
+            TypeTag::Uint8 => "Uint8",
+            TypeTag::Int8 => "Int8",
+            TypeTag::Uint16 => "Uint16",
+            TypeTag::Uint32 => "Uint32",
+            TypeTag::Int32 => "Int32",
+            TypeTag::Int64 => "Int64",
+            TypeTag::Float16 => "Float16",
+            TypeTag::BFloat16 => "BFloat16",
+            TypeTag::Float32 => "Float32",
+            TypeTag::Float64 => "Float64",
+            TypeTag::Date32 => "Date32",
+            TypeTag::TimestampMillis => "TimestampMillis",
+            TypeTag::Duration => "Duration",
+        }
+    }
+
+    /// Encodes this type and kind as a single tag byte.
+    ///
+    /// The low 5 bits hold the type and bit 5 holds the kind.
+    pub fn encode(self, kind: TagKind) -> u8 {
+        (kind as u8) << 5 | self as u8
+    }
+
+    /// Decodes a tag byte, returning `None` if it isn't a valid tag.
+    pub fn decode(byte: u8) -> Option<(TypeTag, TagKind)> {
+        let kind = match byte >> 5 {
+            0 => TagKind::Value,
+            1 => TagKind::Elements,
+            _ => return None,
+        };
+        let tag = *TYPE_TAGS.get(((byte & 0x1f) as usize).checked_sub(1)?)?;
+        Some((tag, kind))
+    }
+}
+
+/// Formats a raw tag byte for error messages, e.g. `Float64 value`.
+pub struct DisplayTag(pub u8);
+
+impl fmt::Display for DisplayTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match TypeTag::decode(self.0) {
+            Some((tag, TagKind::Value)) => write!(f, "{} value", tag.name()),
+            Some((tag, TagKind::Elements)) => write!(f, "{} elements", tag.name()),
+            None => write!(f, "unknown tag 0x{:02x}", self.0),
+        }
+    }
+}
+
+/// Associates a channel element type with its type tag.
+pub trait Tagged {
+    const TAG: TypeTag;
+}
+
+macro_rules! impl_tagged {
+    ($($type:ty => $tag:ident),*) => {
+        $(
+            impl Tagged for $type {
+                const TAG: TypeTag = TypeTag::$tag;
+            }
+        )*
+    };
+}
+
+impl_tagged! {
+    u8 => Uint8,
+    i8 => Int8,
+    u16 => Uint16,
+    u32 => Uint32,
+    i32 => Int32,
+    i64 => Int64,
+    F16 => Float16,
+    Bf16 => BFloat16,
+    f32 => Float32,
+    f64 => Float64,
+    Date32 => Date32,
+    TimestampMillis => TimestampMillis,
+    Duration => Duration
+}
+
+impl<'a> Channel<'a> {
+    /// Writes the type tag for `T` when the `type-tags` feature is enabled.
+    #[inline(always)]
+    pub(super) fn write_tag<T: Tagged>(&mut self, kind: TagKind) {
+        if TYPE_TAGS_ENABLED {
+            self.put::<u8>(T::TAG.encode(kind));
+        }
+    }
+
+    /// Verifies the type tag for `T` when the `type-tags` feature is enabled.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the offset, expected and actual types if the tag doesn't
+    /// match, reporting [`ErrorCode::INVALID_INPUT`] to the host.
+    #[inline(always)]
+    pub(super) fn check_tag<T: Tagged>(&self, kind: TagKind) {
+        if TYPE_TAGS_ENABLED {
+            let offset = self.offset.get();
+            let expected = T::TAG.encode(kind);
+            let found = self.take::<u8>();
+            if found != expected {
+                tag_mismatch(offset, expected, found);
+            }
+        }
+    }
+}
+
+#[cold]
+fn tag_mismatch(offset: u32, expected: u8, found: u8) -> ! {
+    // The host wrote a different type than the module reads, rather than the module failing
+    set_panic_code(ErrorCode::INVALID_INPUT);
+
+    panic!(
+        "Type tag mismatch at offset {}: expected {} but found {}",
+        offset,
+        DisplayTag(expected),
+        DisplayTag(found)
+    );
+}
+
+#[cfg(all(test, feature = "type-tags"))]
+mod test;
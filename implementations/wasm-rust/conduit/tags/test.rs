@@ -0,0 +1,116 @@
+use super::super::{Reader, Writer, F16};
+use super::{DisplayTag, TagKind, TypeTag};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tags_prefix_every_value() {
+        let mut storage = [0u64; 32];
+        {
+            let mut writer = Writer::from(&mut storage);
+            writer.write_u8(5);
+            writer.write_u32(7);
+            writer.copy_array_u8(&[1, 2]);
+        }
+
+        let expected = [
+            1, 5, 4, 0, 7, 0, 0, 0, 4, 0, 0, 0, 2, 0, 0, 0, 33, 1, 2, 0, 0, 0, 0, 0u8,
+        ];
+        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 24) };
+        assert_eq!(&expected[..], actual);
+
+        let mut reader = Reader::from(&mut storage);
+        reader.reset();
+
+        assert_eq!(5, reader.read_u8());
+        assert_eq!(7, reader.read_u32());
+        assert_eq!(&[1, 2], reader.read_array_u8());
+    }
+
+    #[test]
+    fn tags_cover_composite_encodings() {
+        let mut storage = [0u64; 32];
+        {
+            let mut writer = Writer::from(&mut storage);
+            writer.begin_message(3);
+            writer.begin_section();
+            writer.write_f16(F16::from_f32(1.0));
+            writer.end_section();
+            writer.end_message();
+        }
+
+        let mut reader = Reader::from(&mut storage);
+        reader.reset();
+
+        reader.read_message_header(3).unwrap();
+        let section = reader.read_section();
+        assert_eq!(1.0, section.read_f16().to_f32());
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Type tag mismatch at offset 0: expected Float32 value but found Float64 value"
+    )]
+    fn mismatched_types_panic() {
+        let mut storage = [0u64; 32];
+        {
+            let mut writer = Writer::from(&mut storage);
+            writer.write_f64(1.0);
+        }
+
+        let reader = Reader::from(&mut storage);
+        reader.read_f32();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Type tag mismatch at offset 8: expected Int32 elements but found Int32 value"
+    )]
+    fn mismatched_kinds_panic() {
+        let mut storage = [0u64; 32];
+        {
+            let mut writer = Writer::from(&mut storage);
+            writer.write_u32(2);
+            writer.write_i32(1);
+        }
+
+        let reader = Reader::from(&mut storage);
+        reader.read_array_i32();
+    }
+
+    #[test]
+    fn reads_a_payload_tagged_by_the_host() {
+        // As the TypeScript host writes it after beginMessage(3, true)
+        let bytes = [
+            90, 65, 87, 77, 1, 0, 1, 0, 3, 0, 0, 0, 32, 0, 0, 0, 1, 5, 4, 0, 7, 0, 0, 0, 4, 0, 0,
+            0, 2, 0, 0, 0, 33, 1, 2, 10, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 248, 63u8,
+        ];
+        let mut storage = [0u64; 6];
+        for (word, chunk) in storage.iter_mut().zip(bytes.chunks(8)) {
+            *word = u64::from_le_bytes(chunk.try_into().unwrap());
+        }
+
+        let mut reader = Reader::from(&mut storage);
+        reader.reset();
+
+        reader.read_message_header(3).unwrap();
+        assert_eq!(5, reader.read_u8());
+        assert_eq!(7, reader.read_u32());
+        assert_eq!(&[1, 2], reader.read_array_u8());
+        assert_eq!(1.5, reader.read_f64());
+    }
+
+    #[test]
+    fn tag_encoding_round_trips() {
+        let byte = TypeTag::Duration.encode(TagKind::Elements);
+        assert_eq!(
+            Some((TypeTag::Duration, TagKind::Elements)),
+            TypeTag::decode(byte)
+        );
+        assert_eq!(None, TypeTag::decode(0));
+        assert_eq!(None, TypeTag::decode(0x1f));
+        assert_eq!("unknown tag 0xff", DisplayTag(0xff).to_string());
+    }
+}
@@ -1,9 +1,22 @@
 use super::{Reader, Writer};
 
 #[cfg(test)]
+#[allow(clippy::approx_constant)]
 mod tests {
     use super::*;
 
+    /// Checks the written bytes against the layout for this build, with or without type tags.
+    fn assert_layout(storage: &[u64], untagged: &[u8], tagged: &[u8]) {
+        let expected = if cfg!(feature = "type-tags") {
+            tagged
+        } else {
+            untagged
+        };
+        let actual =
+            unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, expected.len()) };
+        assert_eq!(expected, actual);
+    }
+
     #[test]
     fn simple_uint8_write() {
         let mut storage = [0u64; 32];
@@ -12,9 +25,11 @@ mod tests {
             writer.write_u8(42);
         }
 
-        let expected = [42, 0, 0, 0, 0, 0, 0, 0u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 8) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[42, 0, 0, 0, 0, 0, 0, 0u8],
+            &[1, 42, 0, 0, 0, 0, 0, 0u8],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -32,9 +47,11 @@ mod tests {
             writer.write_u8(30);
         }
 
-        let expected = [10, 20, 30, 0, 0, 0, 0, 0u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 8) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[10, 20, 30, 0, 0, 0, 0, 0u8],
+            &[1, 10, 1, 20, 1, 30, 0, 0u8],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -52,9 +69,11 @@ mod tests {
             writer.write_u32(0x12345678);
         }
 
-        let expected = [120, 86, 52, 18, 0, 0, 0, 0u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 8) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[120, 86, 52, 18, 0, 0, 0, 0u8],
+            &[4, 0, 0, 0, 120, 86, 52, 18u8],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -71,9 +90,11 @@ mod tests {
             writer.write_u32(0x12345678);
         }
 
-        let expected = [10, 0, 0, 0, 120, 86, 52, 18u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 8) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[10, 0, 0, 0, 120, 86, 52, 18u8],
+            &[1, 10, 4, 0, 120, 86, 52, 18u8],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -90,9 +111,11 @@ mod tests {
             writer.write_i32(i32::MIN);
         }
 
-        let expected = [0, 0, 0, 128, 0, 0, 0, 0u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 8) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[0, 0, 0, 128, 0, 0, 0, 0u8],
+            &[5, 0, 0, 0, 0, 0, 0, 128u8],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -109,9 +132,11 @@ mod tests {
             writer.write_i32(-42);
         }
 
-        let expected = [10, 0, 0, 0, 214, 255, 255, 255u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 8) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[10, 0, 0, 0, 214, 255, 255, 255u8],
+            &[1, 10, 5, 0, 214, 255, 255, 255u8],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -128,9 +153,11 @@ mod tests {
             writer.write_f32(3.14159);
         }
 
-        let expected = [208, 15, 73, 64, 0, 0, 0, 0u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 8) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[208, 15, 73, 64, 0, 0, 0, 0u8],
+            &[9, 0, 0, 0, 208, 15, 73, 64u8],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -147,9 +174,11 @@ mod tests {
             writer.write_f32(3.14159);
         }
 
-        let expected = [5, 0, 0, 0, 208, 15, 73, 64u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 8) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[5, 0, 0, 0, 208, 15, 73, 64u8],
+            &[1, 5, 9, 0, 208, 15, 73, 64u8],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -168,9 +197,13 @@ mod tests {
             writer.write_f32(0.0);
         }
 
-        let expected = [0, 0, 128, 63, 0, 0, 128, 191, 0, 0, 0, 0u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 12) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[0, 0, 128, 63, 0, 0, 128, 191, 0, 0, 0, 0u8],
+            &[
+                9, 0, 0, 0, 0, 0, 128, 63, 9, 0, 0, 0, 0, 0, 128, 191, 9, 0, 0, 0, 0, 0, 0, 0u8,
+            ],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -188,11 +221,15 @@ mod tests {
             writer.write_f64(1.7976931348623157e+308);
         }
 
-        let expected = [
-            255, 255, 255, 255, 255, 255, 239, 127, 0, 0, 0, 0, 0, 0, 0, 0u8,
-        ];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 16) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[
+                255, 255, 255, 255, 255, 255, 239, 127, 0, 0, 0, 0, 0, 0, 0, 0u8,
+            ],
+            &[
+                10, 0, 0, 0, 0, 0, 0, 0, 255, 255, 255, 255, 255, 255, 239, 127u8,
+            ],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -209,9 +246,11 @@ mod tests {
             writer.write_f64(3.14159);
         }
 
-        let expected = [5, 0, 0, 0, 0, 0, 0, 0, 110, 134, 27, 240, 249, 33, 9, 64u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 16) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[5, 0, 0, 0, 0, 0, 0, 0, 110, 134, 27, 240, 249, 33, 9, 64u8],
+            &[1, 5, 10, 0, 0, 0, 0, 0, 110, 134, 27, 240, 249, 33, 9, 64u8],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -232,12 +271,17 @@ mod tests {
             writer.write_f64(-2.718);
         }
 
-        let expected = [
-            255, 0, 0, 0, 152, 186, 220, 254, 255, 255, 255, 255, 182, 243, 45, 64, 88, 57, 180,
-            200, 118, 190, 5, 192u8,
-        ];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 24) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[
+                255, 0, 0, 0, 152, 186, 220, 254, 255, 255, 255, 255, 182, 243, 45, 64, 88, 57,
+                180, 200, 118, 190, 5, 192u8,
+            ],
+            &[
+                1, 255, 4, 0, 152, 186, 220, 254, 5, 0, 0, 0, 255, 255, 255, 255, 9, 0, 0, 0, 182,
+                243, 45, 64, 10, 0, 0, 0, 0, 0, 0, 0, 88, 57, 180, 200, 118, 190, 5, 192u8,
+            ],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -257,9 +301,11 @@ mod tests {
             writer.copy_array_u8(&[]);
         }
 
-        let expected = [0, 0, 0, 0, 0, 0, 0, 0u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 8) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[0, 0, 0, 0, 0, 0, 0, 0u8],
+            &[4, 0, 0, 0, 0, 0, 0, 0, 33, 0, 0, 0, 0, 0, 0, 0u8],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -276,9 +322,11 @@ mod tests {
             writer.copy_array_u8(&[1, 2, 3, 4, 5]);
         }
 
-        let expected = [5, 0, 0, 0, 1, 2, 3, 4, 5, 0, 0, 0u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 12) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[5, 0, 0, 0, 1, 2, 3, 4, 5, 0, 0, 0u8],
+            &[4, 0, 0, 0, 5, 0, 0, 0, 33, 1, 2, 3, 4, 5, 0, 0u8],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -300,9 +348,14 @@ mod tests {
             writer.copy_array_u32(&[0x12345678, 0x87654321]);
         }
 
-        let expected = [2, 0, 0, 0, 120, 86, 52, 18, 33, 67, 101, 135u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 12) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[2, 0, 0, 0, 120, 86, 52, 18, 33, 67, 101, 135u8],
+            &[
+                4, 0, 0, 0, 2, 0, 0, 0, 36, 0, 0, 0, 120, 86, 52, 18, 33, 67, 101, 135, 0, 0, 0,
+                0u8,
+            ],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -322,9 +375,14 @@ mod tests {
             writer.copy_array_u32(&[0x11223344, 0x55667788]);
         }
 
-        let expected = [42, 0, 0, 0, 2, 0, 0, 0, 68, 51, 34, 17, 136, 119, 102, 85u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 16) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[42, 0, 0, 0, 2, 0, 0, 0, 68, 51, 34, 17, 136, 119, 102, 85u8],
+            &[
+                1, 42, 4, 0, 2, 0, 0, 0, 36, 0, 0, 0, 68, 51, 34, 17, 136, 119, 102, 85, 0, 0, 0,
+                0u8,
+            ],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -344,9 +402,13 @@ mod tests {
             writer.copy_array_i32(&[i32::MIN, 0, 2147483647]);
         }
 
-        let expected = [3, 0, 0, 0, 0, 0, 0, 128, 0, 0, 0, 0, 255, 255, 255, 127u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 16) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[3, 0, 0, 0, 0, 0, 0, 128, 0, 0, 0, 0, 255, 255, 255, 127u8],
+            &[
+                4, 0, 0, 0, 3, 0, 0, 0, 37, 0, 0, 0, 0, 0, 0, 128, 0, 0, 0, 0, 255, 255, 255, 127u8,
+            ],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -367,11 +429,15 @@ mod tests {
             writer.copy_array_i32(&[-1, 0, 1]);
         }
 
-        let expected = [
-            255, 0, 0, 0, 3, 0, 0, 0, 255, 255, 255, 255, 0, 0, 0, 0, 1, 0, 0, 0u8,
-        ];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 20) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[
+                255, 0, 0, 0, 3, 0, 0, 0, 255, 255, 255, 255, 0, 0, 0, 0, 1, 0, 0, 0u8,
+            ],
+            &[
+                1, 255, 4, 0, 3, 0, 0, 0, 37, 0, 0, 0, 255, 255, 255, 255, 0, 0, 0, 0, 1, 0, 0, 0u8,
+            ],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -392,9 +458,11 @@ mod tests {
             writer.copy_array_f32(&[]);
         }
 
-        let expected = [0, 0, 0, 0, 0, 0, 0, 0u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 8) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[0, 0, 0, 0, 0, 0, 0, 0u8],
+            &[4, 0, 0, 0, 0, 0, 0, 0, 41, 0, 0, 0, 0, 0, 0, 0u8],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -411,9 +479,14 @@ mod tests {
             writer.copy_array_f32(&[1.0, -1.0, 3.14159]);
         }
 
-        let expected = [3, 0, 0, 0, 0, 0, 128, 63, 0, 0, 128, 191, 208, 15, 73, 64u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 16) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[3, 0, 0, 0, 0, 0, 128, 63, 0, 0, 128, 191, 208, 15, 73, 64u8],
+            &[
+                4, 0, 0, 0, 3, 0, 0, 0, 41, 0, 0, 0, 0, 0, 128, 63, 0, 0, 128, 191, 208, 15, 73,
+                64u8,
+            ],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -434,11 +507,16 @@ mod tests {
             writer.copy_array_f32(&[2.718, -2.718]);
         }
 
-        let expected = [
-            42, 0, 0, 0, 2, 0, 0, 0, 182, 243, 45, 64, 182, 243, 45, 192u8,
-        ];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 16) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[
+                42, 0, 0, 0, 2, 0, 0, 0, 182, 243, 45, 64, 182, 243, 45, 192u8,
+            ],
+            &[
+                1, 42, 4, 0, 2, 0, 0, 0, 41, 0, 0, 0, 182, 243, 45, 64, 182, 243, 45, 192, 0, 0, 0,
+                0u8,
+            ],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -458,12 +536,17 @@ mod tests {
             writer.copy_array_f64(&[0.0, 3.14159, -2.718]);
         }
 
-        let expected = [
-            3, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 110, 134, 27, 240, 249, 33, 9, 64, 88,
-            57, 180, 200, 118, 190, 5, 192u8,
-        ];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 32) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[
+                3, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 110, 134, 27, 240, 249, 33, 9, 64,
+                88, 57, 180, 200, 118, 190, 5, 192u8,
+            ],
+            &[
+                4, 0, 0, 0, 3, 0, 0, 0, 42, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 110, 134,
+                27, 240, 249, 33, 9, 64, 88, 57, 180, 200, 118, 190, 5, 192u8,
+            ],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -484,12 +567,17 @@ mod tests {
             writer.copy_array_f64(&[1.0, -1.0]);
         }
 
-        let expected = [
-            42, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 240, 63, 0, 0, 0, 0, 0, 0, 240, 191, 0, 0,
-            0, 0, 0, 0, 0, 0u8,
-        ];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 32) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[
+                42, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 240, 63, 0, 0, 0, 0, 0, 0, 240, 191, 0,
+                0, 0, 0, 0, 0, 0, 0u8,
+            ],
+            &[
+                1, 42, 4, 0, 2, 0, 0, 0, 42, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 240, 63, 0, 0,
+                0, 0, 0, 0, 240, 191u8,
+            ],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -509,9 +597,11 @@ mod tests {
             writer.copy_elements_u8(&[]);
         }
 
-        let expected = [0, 0, 0, 0, 0, 0, 0, 0u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 8) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[0, 0, 0, 0, 0, 0, 0, 0u8],
+            &[33, 0, 0, 0, 0, 0, 0, 0u8],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -528,9 +618,11 @@ mod tests {
             writer.copy_elements_u8(&[10, 20, 30, 40, 50]);
         }
 
-        let expected = [10, 20, 30, 40, 50, 0, 0, 0u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 8) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[10, 20, 30, 40, 50, 0, 0, 0u8],
+            &[33, 10, 20, 30, 40, 50, 0, 0u8],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -551,9 +643,11 @@ mod tests {
             writer.copy_elements_u32(&[0x12345678, 0x87654321]);
         }
 
-        let expected = [120, 86, 52, 18, 33, 67, 101, 135u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 8) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[120, 86, 52, 18, 33, 67, 101, 135u8],
+            &[36, 0, 0, 0, 120, 86, 52, 18, 33, 67, 101, 135, 0, 0, 0, 0u8],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -572,9 +666,13 @@ mod tests {
             writer.copy_elements_u32(&[0x11223344, 0x55667788]);
         }
 
-        let expected = [42, 0, 0, 0, 68, 51, 34, 17, 136, 119, 102, 85u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 12) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[42, 0, 0, 0, 68, 51, 34, 17, 136, 119, 102, 85u8],
+            &[
+                1, 42, 36, 0, 68, 51, 34, 17, 136, 119, 102, 85, 0, 0, 0, 0u8,
+            ],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -593,9 +691,11 @@ mod tests {
             writer.copy_elements_i32(&[i32::MIN, 0, 2147483647]);
         }
 
-        let expected = [0, 0, 0, 128, 0, 0, 0, 0, 255, 255, 255, 127u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 12) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[0, 0, 0, 128, 0, 0, 0, 0, 255, 255, 255, 127u8],
+            &[37, 0, 0, 0, 0, 0, 0, 128, 0, 0, 0, 0, 255, 255, 255, 127u8],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -615,9 +715,11 @@ mod tests {
             writer.copy_elements_i32(&[-1, 0, 1]);
         }
 
-        let expected = [255, 0, 0, 0, 255, 255, 255, 255, 0, 0, 0, 0, 1, 0, 0, 0u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 16) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[255, 0, 0, 0, 255, 255, 255, 255, 0, 0, 0, 0, 1, 0, 0, 0u8],
+            &[1, 255, 37, 0, 255, 255, 255, 255, 0, 0, 0, 0, 1, 0, 0, 0u8],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -637,9 +739,11 @@ mod tests {
             writer.copy_elements_f32(&[]);
         }
 
-        let expected = [0, 0, 0, 0, 0, 0, 0, 0u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 8) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[0, 0, 0, 0, 0, 0, 0, 0u8],
+            &[41, 0, 0, 0, 0, 0, 0, 0u8],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -656,9 +760,11 @@ mod tests {
             writer.copy_elements_f32(&[0.0, 1.0, -1.0]);
         }
 
-        let expected = [0, 0, 0, 0, 0, 0, 128, 63, 0, 0, 128, 191u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 12) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[0, 0, 0, 0, 0, 0, 128, 63, 0, 0, 128, 191u8],
+            &[41, 0, 0, 0, 0, 0, 0, 0, 0, 0, 128, 63, 0, 0, 128, 191u8],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -678,9 +784,13 @@ mod tests {
             writer.copy_elements_f32(&[3.14159, 2.718]);
         }
 
-        let expected = [42, 0, 0, 0, 208, 15, 73, 64, 182, 243, 45, 64u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 12) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[42, 0, 0, 0, 208, 15, 73, 64, 182, 243, 45, 64u8],
+            &[
+                1, 42, 41, 0, 208, 15, 73, 64, 182, 243, 45, 64, 0, 0, 0, 0u8,
+            ],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -699,9 +809,14 @@ mod tests {
             writer.copy_elements_f64(&[0.0, 3.14159]);
         }
 
-        let expected = [0, 0, 0, 0, 0, 0, 0, 0, 110, 134, 27, 240, 249, 33, 9, 64u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 16) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[0, 0, 0, 0, 0, 0, 0, 0, 110, 134, 27, 240, 249, 33, 9, 64u8],
+            &[
+                42, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 110, 134, 27, 240, 249, 33, 9,
+                64u8,
+            ],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -720,11 +835,15 @@ mod tests {
             writer.copy_elements_f64(&[1.0, -1.0]);
         }
 
-        let expected = [
-            42, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 240, 63, 0, 0, 0, 0, 0, 0, 240, 191u8,
-        ];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 24) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[
+                42, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 240, 63, 0, 0, 0, 0, 0, 0, 240, 191u8,
+            ],
+            &[
+                1, 42, 42, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 240, 63, 0, 0, 0, 0, 0, 0, 240, 191u8,
+            ],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -743,9 +862,11 @@ mod tests {
             writer.copy_elements_u32(&[10, 20, 30]);
         }
 
-        let expected = [10, 0, 0, 0, 20, 0, 0, 0, 30, 0, 0, 0u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 12) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[10, 0, 0, 0, 20, 0, 0, 0, 30, 0, 0, 0u8],
+            &[36, 0, 0, 0, 10, 0, 0, 0, 20, 0, 0, 0, 30, 0, 0, 0u8],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -768,13 +889,19 @@ mod tests {
             writer.copy_elements_f64(&[9.876]);
         }
 
-        let expected = [
-            255, 0, 0, 0, 1, 239, 205, 171, 3, 0, 0, 0, 156, 255, 255, 255, 0, 0, 0, 0, 100, 0, 0,
-            0, 182, 243, 157, 63, 45, 178, 181, 64, 141, 151, 110, 18, 131, 192, 35, 64, 0, 0, 0,
-            0, 0, 0, 0, 0u8,
-        ];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 48) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[
+                255, 0, 0, 0, 1, 239, 205, 171, 3, 0, 0, 0, 156, 255, 255, 255, 0, 0, 0, 0, 100, 0,
+                0, 0, 182, 243, 157, 63, 45, 178, 181, 64, 141, 151, 110, 18, 131, 192, 35, 64, 0,
+                0, 0, 0, 0, 0, 0, 0u8,
+            ],
+            &[
+                1, 255, 4, 0, 1, 239, 205, 171, 4, 0, 0, 0, 3, 0, 0, 0, 37, 0, 0, 0, 156, 255, 255,
+                255, 0, 0, 0, 0, 100, 0, 0, 0, 41, 0, 0, 0, 182, 243, 157, 63, 45, 178, 181, 64,
+                42, 0, 0, 0, 141, 151, 110, 18, 131, 192, 35, 64u8,
+            ],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -815,17 +942,30 @@ mod tests {
             writer.copy_elements_f64(&[3.141592653589793, -3.141592653589793]);
         }
 
-        let expected = [
-            42, 0, 0, 0, 120, 86, 52, 18, 214, 255, 255, 255, 208, 15, 73, 64, 144, 247, 170, 149,
-            9, 191, 5, 64, 3, 0, 0, 0, 1, 2, 3, 0, 2, 0, 0, 0, 221, 204, 187, 170, 17, 0, 255, 238,
-            3, 0, 0, 0, 255, 255, 255, 255, 0, 0, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0, 0, 0, 128, 63, 0,
-            0, 128, 191, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 240, 63, 0, 0, 0, 0, 0, 0, 240,
-            191, 10, 20, 30, 0, 68, 51, 34, 17, 136, 119, 102, 85, 156, 255, 255, 255, 0, 0, 0, 0,
-            100, 0, 0, 0, 182, 243, 45, 64, 182, 243, 45, 192, 24, 45, 68, 84, 251, 33, 9, 64, 24,
-            45, 68, 84, 251, 33, 9, 192, 0, 0, 0, 0, 0, 0, 0, 0u8,
-        ];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 152) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[
+                42, 0, 0, 0, 120, 86, 52, 18, 214, 255, 255, 255, 208, 15, 73, 64, 144, 247, 170,
+                149, 9, 191, 5, 64, 3, 0, 0, 0, 1, 2, 3, 0, 2, 0, 0, 0, 221, 204, 187, 170, 17, 0,
+                255, 238, 3, 0, 0, 0, 255, 255, 255, 255, 0, 0, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0, 0, 0,
+                128, 63, 0, 0, 128, 191, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 240, 63, 0, 0,
+                0, 0, 0, 0, 240, 191, 10, 20, 30, 0, 68, 51, 34, 17, 136, 119, 102, 85, 156, 255,
+                255, 255, 0, 0, 0, 0, 100, 0, 0, 0, 182, 243, 45, 64, 182, 243, 45, 192, 24, 45,
+                68, 84, 251, 33, 9, 64, 24, 45, 68, 84, 251, 33, 9, 192, 0, 0, 0, 0, 0, 0, 0, 0u8,
+            ],
+            &[
+                1, 42, 4, 0, 120, 86, 52, 18, 5, 0, 0, 0, 214, 255, 255, 255, 9, 0, 0, 0, 208, 15,
+                73, 64, 10, 0, 0, 0, 0, 0, 0, 0, 144, 247, 170, 149, 9, 191, 5, 64, 4, 0, 0, 0, 3,
+                0, 0, 0, 33, 1, 2, 3, 4, 0, 0, 0, 2, 0, 0, 0, 36, 0, 0, 0, 221, 204, 187, 170, 17,
+                0, 255, 238, 4, 0, 0, 0, 3, 0, 0, 0, 37, 0, 0, 0, 255, 255, 255, 255, 0, 0, 0, 0,
+                1, 0, 0, 0, 4, 0, 0, 0, 2, 0, 0, 0, 41, 0, 0, 0, 0, 0, 128, 63, 0, 0, 128, 191, 4,
+                0, 0, 0, 2, 0, 0, 0, 42, 0, 0, 0, 0, 0, 0, 0, 0, 0, 240, 63, 0, 0, 0, 0, 0, 0, 240,
+                191, 33, 10, 20, 30, 36, 0, 0, 0, 68, 51, 34, 17, 136, 119, 102, 85, 37, 0, 0, 0,
+                156, 255, 255, 255, 0, 0, 0, 0, 100, 0, 0, 0, 41, 0, 0, 0, 182, 243, 45, 64, 182,
+                243, 45, 192, 42, 0, 0, 0, 24, 45, 68, 84, 251, 33, 9, 64, 24, 45, 68, 84, 251, 33,
+                9, 192u8,
+            ],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -887,12 +1027,18 @@ mod tests {
             writer.copy_array_f64(&[1.1, 2.2]);
         }
 
-        let expected = [
-            99, 0, 0, 0, 42, 0, 0, 0, 0, 0, 192, 63, 2, 0, 0, 0, 154, 153, 153, 153, 153, 153, 241,
-            63, 154, 153, 153, 153, 153, 153, 1, 64u8,
-        ];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 32) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[
+                99, 0, 0, 0, 42, 0, 0, 0, 0, 0, 192, 63, 2, 0, 0, 0, 154, 153, 153, 153, 153, 153,
+                241, 63, 154, 153, 153, 153, 153, 153, 1, 64u8,
+            ],
+            &[
+                1, 99, 4, 0, 42, 0, 0, 0, 9, 0, 0, 0, 0, 0, 192, 63, 4, 0, 0, 0, 2, 0, 0, 0, 42, 0,
+                0, 0, 0, 0, 0, 0, 154, 153, 153, 153, 153, 153, 241, 63, 154, 153, 153, 153, 153,
+                153, 1, 64u8,
+            ],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -920,12 +1066,17 @@ mod tests {
             writer.write_u8(7);
         }
 
-        let expected = [
-            1, 0, 0, 0, 2, 0, 0, 0, 3, 0, 0, 0, 0, 0, 128, 64, 5, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-            0, 0, 24, 64, 7, 0, 0, 0, 0, 0, 0, 0u8,
-        ];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 40) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[
+                1, 0, 0, 0, 2, 0, 0, 0, 3, 0, 0, 0, 0, 0, 128, 64, 5, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+                0, 0, 0, 24, 64, 7, 0, 0, 0, 0, 0, 0, 0u8,
+            ],
+            &[
+                1, 1, 4, 0, 2, 0, 0, 0, 1, 3, 9, 0, 0, 0, 128, 64, 1, 5, 10, 0, 0, 0, 0, 0, 0, 0,
+                0, 0, 0, 0, 24, 64, 1, 7, 0, 0, 0, 0, 0, 0u8,
+            ],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -951,9 +1102,15 @@ mod tests {
             writer.copy_array_f64(&[]);
         }
 
-        let expected = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 20) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0u8],
+            &[
+                4, 0, 0, 0, 0, 0, 0, 0, 33, 4, 0, 0, 0, 0, 0, 0, 36, 0, 0, 0, 4, 0, 0, 0, 0, 0, 0,
+                0, 37, 0, 0, 0, 4, 0, 0, 0, 0, 0, 0, 0, 41, 0, 0, 0, 4, 0, 0, 0, 0, 0, 0, 0, 42, 0,
+                0, 0u8,
+            ],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -981,11 +1138,16 @@ mod tests {
             writer.write_u8(20);
         }
 
-        let expected = [
-            10, 0, 0, 0, 120, 86, 52, 18, 164, 112, 157, 63, 20, 0, 0, 0u8,
-        ];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 16) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[
+                10, 0, 0, 0, 120, 86, 52, 18, 164, 112, 157, 63, 20, 0, 0, 0u8,
+            ],
+            &[
+                1, 10, 4, 0, 120, 86, 52, 18, 9, 0, 0, 0, 164, 112, 157, 63, 1, 20, 0, 0, 0, 0, 0,
+                0u8,
+            ],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -1019,14 +1181,23 @@ mod tests {
             writer.write_f64(5e-324);
         }
 
-        let expected = [
-            0, 127, 255, 0, 0, 0, 0, 0, 255, 255, 255, 127, 255, 255, 255, 255, 0, 0, 0, 128, 0, 0,
-            0, 0, 255, 255, 255, 127, 0, 0, 0, 0, 253, 255, 127, 0, 255, 255, 127, 127, 0, 0, 0, 0,
-            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 128, 255, 255, 255, 255, 255, 255, 239, 127, 1, 0, 0,
-            0, 0, 0, 0, 0u8,
-        ];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 72) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[
+                0, 127, 255, 0, 0, 0, 0, 0, 255, 255, 255, 127, 255, 255, 255, 255, 0, 0, 0, 128,
+                0, 0, 0, 0, 255, 255, 255, 127, 0, 0, 0, 0, 253, 255, 127, 0, 255, 255, 127, 127,
+                0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 128, 255, 255, 255, 255, 255, 255,
+                239, 127, 1, 0, 0, 0, 0, 0, 0, 0u8,
+            ],
+            &[
+                1, 0, 1, 127, 1, 255, 4, 0, 0, 0, 0, 0, 4, 0, 0, 0, 255, 255, 255, 127, 4, 0, 0, 0,
+                255, 255, 255, 255, 5, 0, 0, 0, 0, 0, 0, 128, 5, 0, 0, 0, 0, 0, 0, 0, 5, 0, 0, 0,
+                255, 255, 255, 127, 9, 0, 0, 0, 0, 0, 0, 0, 9, 0, 0, 0, 253, 255, 127, 0, 9, 0, 0,
+                0, 255, 255, 127, 127, 10, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 10, 0, 0, 0, 0, 0, 0,
+                0, 0, 0, 0, 0, 0, 0, 0, 128, 10, 0, 0, 0, 0, 0, 0, 0, 255, 255, 255, 255, 255, 255,
+                239, 127, 10, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0u8,
+            ],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -1060,9 +1231,11 @@ mod tests {
             }
         }
 
-        let expected = [42, 0, 0, 0, 0, 0, 0, 0u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 8) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[42, 0, 0, 0, 0, 0, 0, 0u8],
+            &[1, 42, 0, 0, 0, 0, 0, 0u8],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -1089,9 +1262,11 @@ mod tests {
             }
         }
 
-        let expected = [10, 20, 30, 0, 0, 0, 0, 0u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 8) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[10, 20, 30, 0, 0, 0, 0, 0u8],
+            &[1, 10, 1, 20, 1, 30, 0, 0u8],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -1112,9 +1287,11 @@ mod tests {
             }
         }
 
-        let expected = [120, 86, 52, 18, 0, 0, 0, 0u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 8) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[120, 86, 52, 18, 0, 0, 0, 0u8],
+            &[4, 0, 0, 0, 120, 86, 52, 18u8],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -1134,9 +1311,11 @@ mod tests {
             }
         }
 
-        let expected = [10, 0, 0, 0, 120, 86, 52, 18u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 8) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[10, 0, 0, 0, 120, 86, 52, 18u8],
+            &[1, 10, 4, 0, 120, 86, 52, 18u8],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -1156,9 +1335,11 @@ mod tests {
             }
         }
 
-        let expected = [0, 0, 0, 128, 0, 0, 0, 0u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 8) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[0, 0, 0, 128, 0, 0, 0, 0u8],
+            &[5, 0, 0, 0, 0, 0, 0, 128u8],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -1177,9 +1358,11 @@ mod tests {
             }
         }
 
-        let expected = [208, 15, 73, 64, 0, 0, 0, 0u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 8) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[208, 15, 73, 64, 0, 0, 0, 0u8],
+            &[9, 0, 0, 0, 208, 15, 73, 64u8],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -1199,9 +1382,11 @@ mod tests {
             }
         }
 
-        let expected = [5, 0, 0, 0, 208, 15, 73, 64u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 8) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[5, 0, 0, 0, 208, 15, 73, 64u8],
+            &[1, 5, 9, 0, 208, 15, 73, 64u8],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -1221,9 +1406,11 @@ mod tests {
             }
         }
 
-        let expected = [110, 134, 27, 240, 249, 33, 9, 64, 0, 0, 0, 0, 0, 0, 0, 0u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 16) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[110, 134, 27, 240, 249, 33, 9, 64, 0, 0, 0, 0, 0, 0, 0, 0u8],
+            &[10, 0, 0, 0, 0, 0, 0, 0, 110, 134, 27, 240, 249, 33, 9, 64u8],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -1243,9 +1430,11 @@ mod tests {
             }
         }
 
-        let expected = [5, 0, 0, 0, 0, 0, 0, 0, 110, 134, 27, 240, 249, 33, 9, 64u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 16) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[5, 0, 0, 0, 0, 0, 0, 0, 110, 134, 27, 240, 249, 33, 9, 64u8],
+            &[1, 5, 10, 0, 0, 0, 0, 0, 110, 134, 27, 240, 249, 33, 9, 64u8],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -1281,12 +1470,17 @@ mod tests {
             }
         }
 
-        let expected = [
-            255, 0, 0, 0, 152, 186, 220, 254, 255, 255, 255, 255, 182, 243, 45, 64, 88, 57, 180,
-            200, 118, 190, 5, 192u8,
-        ];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 24) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[
+                255, 0, 0, 0, 152, 186, 220, 254, 255, 255, 255, 255, 182, 243, 45, 64, 88, 57,
+                180, 200, 118, 190, 5, 192u8,
+            ],
+            &[
+                1, 255, 4, 0, 152, 186, 220, 254, 5, 0, 0, 0, 255, 255, 255, 255, 9, 0, 0, 0, 182,
+                243, 45, 64, 10, 0, 0, 0, 0, 0, 0, 0, 88, 57, 180, 200, 118, 190, 5, 192u8,
+            ],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -1306,9 +1500,11 @@ mod tests {
             let _arr0 = writer.init_array_u8(0);
         }
 
-        let expected = [0, 0, 0, 0, 0, 0, 0, 0u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 8) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[0, 0, 0, 0, 0, 0, 0, 0u8],
+            &[4, 0, 0, 0, 0, 0, 0, 0, 33, 0, 0, 0, 0, 0, 0, 0u8],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -1330,9 +1526,11 @@ mod tests {
             arr0[4] = 5;
         }
 
-        let expected = [5, 0, 0, 0, 1, 2, 3, 4, 5, 0, 0, 0u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 12) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[5, 0, 0, 0, 1, 2, 3, 4, 5, 0, 0, 0u8],
+            &[4, 0, 0, 0, 5, 0, 0, 0, 33, 1, 2, 3, 4, 5, 0, 0u8],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -1356,9 +1554,14 @@ mod tests {
             arr0[1] = 0x87654321;
         }
 
-        let expected = [2, 0, 0, 0, 120, 86, 52, 18, 33, 67, 101, 135u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 12) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[2, 0, 0, 0, 120, 86, 52, 18, 33, 67, 101, 135u8],
+            &[
+                4, 0, 0, 0, 2, 0, 0, 0, 36, 0, 0, 0, 120, 86, 52, 18, 33, 67, 101, 135, 0, 0, 0,
+                0u8,
+            ],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -1380,9 +1583,14 @@ mod tests {
             arr1[1] = 0x55667788;
         }
 
-        let expected = [42, 0, 0, 0, 2, 0, 0, 0, 68, 51, 34, 17, 136, 119, 102, 85u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 16) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[42, 0, 0, 0, 2, 0, 0, 0, 68, 51, 34, 17, 136, 119, 102, 85u8],
+            &[
+                1, 42, 4, 0, 2, 0, 0, 0, 36, 0, 0, 0, 68, 51, 34, 17, 136, 119, 102, 85, 0, 0, 0,
+                0u8,
+            ],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -1405,9 +1613,13 @@ mod tests {
             arr0[2] = 2147483647;
         }
 
-        let expected = [3, 0, 0, 0, 0, 0, 0, 128, 0, 0, 0, 0, 255, 255, 255, 127u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 16) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[3, 0, 0, 0, 0, 0, 0, 128, 0, 0, 0, 0, 255, 255, 255, 127u8],
+            &[
+                4, 0, 0, 0, 3, 0, 0, 0, 37, 0, 0, 0, 0, 0, 0, 128, 0, 0, 0, 0, 255, 255, 255, 127u8,
+            ],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -1430,9 +1642,14 @@ mod tests {
             arr0[2] = -2.718;
         }
 
-        let expected = [3, 0, 0, 0, 0, 0, 0, 0, 208, 15, 73, 64, 182, 243, 45, 192u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 16) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[3, 0, 0, 0, 0, 0, 0, 0, 208, 15, 73, 64, 182, 243, 45, 192u8],
+            &[
+                4, 0, 0, 0, 3, 0, 0, 0, 41, 0, 0, 0, 0, 0, 0, 0, 208, 15, 73, 64, 182, 243, 45,
+                192u8,
+            ],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -1455,12 +1672,17 @@ mod tests {
             arr0[2] = -2.718;
         }
 
-        let expected = [
-            3, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 110, 134, 27, 240, 249, 33, 9, 64, 88,
-            57, 180, 200, 118, 190, 5, 192u8,
-        ];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 32) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[
+                3, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 110, 134, 27, 240, 249, 33, 9, 64,
+                88, 57, 180, 200, 118, 190, 5, 192u8,
+            ],
+            &[
+                4, 0, 0, 0, 3, 0, 0, 0, 42, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 110, 134,
+                27, 240, 249, 33, 9, 64, 88, 57, 180, 200, 118, 190, 5, 192u8,
+            ],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -1480,9 +1702,11 @@ mod tests {
             let _arr0 = writer.init_elements_u8(0);
         }
 
-        let expected = [0, 0, 0, 0, 0, 0, 0, 0u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 8) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[0, 0, 0, 0, 0, 0, 0, 0u8],
+            &[33, 0, 0, 0, 0, 0, 0, 0u8],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -1504,9 +1728,11 @@ mod tests {
             arr0[4] = 50;
         }
 
-        let expected = [10, 20, 30, 40, 50, 0, 0, 0u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 8) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[10, 20, 30, 40, 50, 0, 0, 0u8],
+            &[33, 10, 20, 30, 40, 50, 0, 0u8],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -1529,9 +1755,11 @@ mod tests {
             arr0[1] = 0x87654321;
         }
 
-        let expected = [120, 86, 52, 18, 33, 67, 101, 135u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 8) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[120, 86, 52, 18, 33, 67, 101, 135u8],
+            &[36, 0, 0, 0, 120, 86, 52, 18, 33, 67, 101, 135, 0, 0, 0, 0u8],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -1552,9 +1780,13 @@ mod tests {
             arr1[1] = 0x55667788;
         }
 
-        let expected = [42, 0, 0, 0, 68, 51, 34, 17, 136, 119, 102, 85u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 12) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[42, 0, 0, 0, 68, 51, 34, 17, 136, 119, 102, 85u8],
+            &[
+                1, 42, 36, 0, 68, 51, 34, 17, 136, 119, 102, 85, 0, 0, 0, 0u8,
+            ],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -1576,9 +1808,11 @@ mod tests {
             arr0[2] = 2147483647;
         }
 
-        let expected = [0, 0, 0, 128, 0, 0, 0, 0, 255, 255, 255, 127u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 12) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[0, 0, 0, 128, 0, 0, 0, 0, 255, 255, 255, 127u8],
+            &[37, 0, 0, 0, 0, 0, 0, 128, 0, 0, 0, 0, 255, 255, 255, 127u8],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -1599,9 +1833,11 @@ mod tests {
             arr0[1] = 3.14159;
         }
 
-        let expected = [0, 0, 0, 0, 208, 15, 73, 64u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 8) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[0, 0, 0, 0, 208, 15, 73, 64u8],
+            &[41, 0, 0, 0, 0, 0, 0, 0, 208, 15, 73, 64, 0, 0, 0, 0u8],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -1621,9 +1857,14 @@ mod tests {
             arr0[1] = 3.14159;
         }
 
-        let expected = [0, 0, 0, 0, 0, 0, 0, 0, 110, 134, 27, 240, 249, 33, 9, 64u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 16) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[0, 0, 0, 0, 0, 0, 0, 0, 110, 134, 27, 240, 249, 33, 9, 64u8],
+            &[
+                42, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 110, 134, 27, 240, 249, 33, 9,
+                64u8,
+            ],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -1652,12 +1893,18 @@ mod tests {
             arr3[1] = 2.2;
         }
 
-        let expected = [
-            99, 0, 0, 0, 42, 0, 0, 0, 0, 0, 192, 63, 2, 0, 0, 0, 154, 153, 153, 153, 153, 153, 241,
-            63, 154, 153, 153, 153, 153, 153, 1, 64u8,
-        ];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 32) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[
+                99, 0, 0, 0, 42, 0, 0, 0, 0, 0, 192, 63, 2, 0, 0, 0, 154, 153, 153, 153, 153, 153,
+                241, 63, 154, 153, 153, 153, 153, 153, 1, 64u8,
+            ],
+            &[
+                1, 99, 4, 0, 42, 0, 0, 0, 9, 0, 0, 0, 0, 0, 192, 63, 4, 0, 0, 0, 2, 0, 0, 0, 42, 0,
+                0, 0, 0, 0, 0, 0, 154, 153, 153, 153, 153, 153, 241, 63, 154, 153, 153, 153, 153,
+                153, 1, 64u8,
+            ],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -1697,13 +1944,19 @@ mod tests {
             arr4[1] = 5.678;
         }
 
-        let expected = [
-            42, 0, 0, 0, 120, 86, 52, 18, 164, 112, 157, 63, 3, 0, 0, 0, 156, 255, 255, 255, 0, 0,
-            0, 0, 100, 0, 0, 0, 0, 0, 0, 0, 88, 57, 180, 200, 118, 190, 243, 63, 131, 192, 202,
-            161, 69, 182, 22, 64, 0, 0, 0, 0u8,
-        ];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 52) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[
+                42, 0, 0, 0, 120, 86, 52, 18, 164, 112, 157, 63, 3, 0, 0, 0, 156, 255, 255, 255, 0,
+                0, 0, 0, 100, 0, 0, 0, 0, 0, 0, 0, 88, 57, 180, 200, 118, 190, 243, 63, 131, 192,
+                202, 161, 69, 182, 22, 64, 0, 0, 0, 0u8,
+            ],
+            &[
+                1, 42, 4, 0, 120, 86, 52, 18, 9, 0, 0, 0, 164, 112, 157, 63, 4, 0, 0, 0, 3, 0, 0,
+                0, 37, 0, 0, 0, 156, 255, 255, 255, 0, 0, 0, 0, 100, 0, 0, 0, 42, 0, 0, 0, 0, 0, 0,
+                0, 88, 57, 180, 200, 118, 190, 243, 63, 131, 192, 202, 161, 69, 182, 22, 64u8,
+            ],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
@@ -1731,9 +1984,14 @@ mod tests {
             writer.write_f32(-3.4028235e+38);
         }
 
-        let expected = [255, 255, 127, 127, 253, 255, 127, 0, 255, 255, 127, 255u8];
-        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 12) };
-        assert_eq!(&expected[..], actual);
+        assert_layout(
+            &storage,
+            &[255, 255, 127, 127, 253, 255, 127, 0, 255, 255, 127, 255u8],
+            &[
+                9, 0, 0, 0, 255, 255, 127, 127, 9, 0, 0, 0, 253, 255, 127, 0, 9, 0, 0, 0, 255, 255,
+                127, 255u8,
+            ],
+        );
 
         let mut reader = Reader::from(&mut storage);
         reader.reset();
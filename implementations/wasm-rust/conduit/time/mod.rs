@@ -0,0 +1,229 @@
+use core::ops::{Add, Sub};
+
+const MILLIS_PER_DAY: i64 = 86_400_000;
+
+/// A calendar date stored as days since the Unix epoch (1970-01-01).
+///
+/// Encoded on the wire as an `Int32`. Calendar helpers use the proleptic
+/// Gregorian calendar and never allocate, so they are safe to call inside kernels.
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date32(pub i32);
+
+/// A point in time stored as milliseconds since the Unix epoch (UTC).
+///
+/// Encoded on the wire as an `Int64`.
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TimestampMillis(pub i64);
+
+/// A signed span of time stored as milliseconds.
+///
+/// Encoded on the wire as an `Int64`.
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Duration(pub i64);
+
+/// Converts a civil date to days since the epoch.
+///
+/// See <http://howardhinnant.github.io/date_algorithms.html#days_from_civil>
+fn days_from_civil(year: i32, month: u32, day: u32) -> i64 {
+    let year = year as i64 - (month <= 2) as i64;
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = month as i64;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Converts days since the epoch to a civil `(year, month, day)`.
+///
+/// See <http://howardhinnant.github.io/date_algorithms.html#civil_from_days>
+fn civil_from_days(days: i64) -> (i32, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + (month <= 2) as i64;
+    (year as i32, month as u32, day as u32)
+}
+
+impl Date32 {
+    /// Creates a date from a calendar year, month (1-12) and day (1-31).
+    ///
+    /// Out of range months and days are not validated; they roll over the same
+    /// way as the underlying day arithmetic.
+    pub fn from_ymd(year: i32, month: u32, day: u32) -> Self {
+        Self(days_from_civil(year, month, day) as i32)
+    }
+
+    /// Returns the number of days since the epoch.
+    pub fn days(self) -> i32 {
+        self.0
+    }
+
+    /// Returns the calendar `(year, month, day)` of this date.
+    pub fn to_ymd(self) -> (i32, u32, u32) {
+        civil_from_days(self.0 as i64)
+    }
+
+    /// Returns the calendar year.
+    pub fn year(self) -> i32 {
+        self.to_ymd().0
+    }
+
+    /// Returns the calendar month (1-12).
+    pub fn month(self) -> u32 {
+        self.to_ymd().1
+    }
+
+    /// Returns the day of the month (1-31).
+    pub fn day(self) -> u32 {
+        self.to_ymd().2
+    }
+
+    /// Returns the day of the year (1-366).
+    pub fn day_of_year(self) -> u32 {
+        let start = days_from_civil(self.year(), 1, 1);
+        (self.0 as i64 - start + 1) as u32
+    }
+
+    /// Returns the ISO 8601 weekday, from 1 (Monday) to 7 (Sunday).
+    pub fn weekday(self) -> u32 {
+        // 1970-01-01 was a Thursday
+        ((self.0 as i64 + 3).rem_euclid(7) + 1) as u32
+    }
+
+    /// Returns the ISO 8601 `(week-based year, week of year)` of this date.
+    ///
+    /// Weeks start on Monday and week 1 is the week containing the year's first
+    /// Thursday, so early January can belong to the previous year's last week.
+    pub fn iso_week(self) -> (i32, u32) {
+        let thursday = self.0 as i64 + 4 - self.weekday() as i64;
+        let (year, _, _) = civil_from_days(thursday);
+        let week = (thursday - days_from_civil(year, 1, 1)) / 7 + 1;
+        (year, week as u32)
+    }
+
+    /// Returns the ISO 8601 week of year (1-53).
+    pub fn week_of_year(self) -> u32 {
+        self.iso_week().1
+    }
+
+    /// Returns the date `days` days after this one.
+    pub fn add_days(self, days: i32) -> Self {
+        Self(self.0 + days)
+    }
+
+    /// Returns the timestamp at midnight UTC at the start of this date.
+    pub fn to_timestamp(self) -> TimestampMillis {
+        TimestampMillis(self.0 as i64 * MILLIS_PER_DAY)
+    }
+}
+
+impl TimestampMillis {
+    /// Returns the number of milliseconds since the epoch.
+    pub fn millis(self) -> i64 {
+        self.0
+    }
+
+    /// Returns the UTC date this timestamp falls on.
+    pub fn date(self) -> Date32 {
+        Date32(self.0.div_euclid(MILLIS_PER_DAY) as i32)
+    }
+
+    /// Returns the milliseconds elapsed since midnight UTC.
+    pub fn millis_of_day(self) -> u32 {
+        self.0.rem_euclid(MILLIS_PER_DAY) as u32
+    }
+}
+
+impl Duration {
+    /// Creates a duration from milliseconds.
+    pub fn from_millis(millis: i64) -> Self {
+        Self(millis)
+    }
+
+    /// Creates a duration from seconds.
+    pub fn from_seconds(seconds: i64) -> Self {
+        Self(seconds * 1000)
+    }
+
+    /// Creates a duration from minutes.
+    pub fn from_minutes(minutes: i64) -> Self {
+        Self(minutes * 60_000)
+    }
+
+    /// Creates a duration from hours.
+    pub fn from_hours(hours: i64) -> Self {
+        Self(hours * 3_600_000)
+    }
+
+    /// Creates a duration from whole days.
+    pub fn from_days(days: i64) -> Self {
+        Self(days * MILLIS_PER_DAY)
+    }
+
+    /// Returns the duration in milliseconds.
+    pub fn as_millis(self) -> i64 {
+        self.0
+    }
+
+    /// Returns the number of whole days in this duration, truncated towards zero.
+    pub fn whole_days(self) -> i64 {
+        self.0 / MILLIS_PER_DAY
+    }
+}
+
+impl Add<Duration> for TimestampMillis {
+    type Output = TimestampMillis;
+
+    fn add(self, rhs: Duration) -> TimestampMillis {
+        TimestampMillis(self.0 + rhs.0)
+    }
+}
+
+impl Sub<Duration> for TimestampMillis {
+    type Output = TimestampMillis;
+
+    fn sub(self, rhs: Duration) -> TimestampMillis {
+        TimestampMillis(self.0 - rhs.0)
+    }
+}
+
+impl Sub for TimestampMillis {
+    type Output = Duration;
+
+    fn sub(self, rhs: TimestampMillis) -> Duration {
+        Duration(self.0 - rhs.0)
+    }
+}
+
+impl Add for Duration {
+    type Output = Duration;
+
+    fn add(self, rhs: Duration) -> Duration {
+        Duration(self.0 + rhs.0)
+    }
+}
+
+impl Sub for Duration {
+    type Output = Duration;
+
+    fn sub(self, rhs: Duration) -> Duration {
+        Duration(self.0 - rhs.0)
+    }
+}
+
+#[cfg(test)]
+mod test;
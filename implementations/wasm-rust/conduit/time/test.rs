@@ -0,0 +1,113 @@
+use super::super::{Reader, Writer};
+use super::{Date32, Duration, TimestampMillis};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn date32_from_ymd() {
+        assert_eq!(0, Date32::from_ymd(1970, 1, 1).days());
+        assert_eq!(-1, Date32::from_ymd(1969, 12, 31).days());
+        assert_eq!(11_017, Date32::from_ymd(2000, 3, 1).days());
+        assert_eq!(19_782, Date32::from_ymd(2024, 2, 29).days());
+    }
+
+    #[test]
+    fn date32_calendar_fields() {
+        let date = Date32::from_ymd(2024, 2, 29);
+        assert_eq!((2024, 2, 29), date.to_ymd());
+        assert_eq!(2024, date.year());
+        assert_eq!(2, date.month());
+        assert_eq!(29, date.day());
+        assert_eq!(60, date.day_of_year());
+        assert_eq!(4, date.weekday());
+
+        assert_eq!((1969, 12, 31), Date32(-1).to_ymd());
+        assert_eq!((1600, 1, 1), Date32::from_ymd(1600, 1, 1).to_ymd());
+    }
+
+    #[test]
+    fn date32_round_trips_across_centuries() {
+        for days in (-200_000..200_000).step_by(37) {
+            let (year, month, day) = Date32(days).to_ymd();
+            assert_eq!(days, Date32::from_ymd(year, month, day).days());
+        }
+    }
+
+    #[test]
+    fn date32_iso_week() {
+        // 2021-01-03 is a Sunday belonging to the last week of 2020
+        assert_eq!((2020, 53), Date32::from_ymd(2021, 1, 3).iso_week());
+        assert_eq!((2021, 1), Date32::from_ymd(2021, 1, 4).iso_week());
+        // 2024-12-30 is a Monday in the first week of 2025
+        assert_eq!((2025, 1), Date32::from_ymd(2024, 12, 30).iso_week());
+        assert_eq!(26, Date32::from_ymd(2024, 6, 27).week_of_year());
+    }
+
+    #[test]
+    fn timestamp_and_duration_arithmetic() {
+        let timestamp = TimestampMillis(1_709_210_096_789);
+        assert_eq!(Date32::from_ymd(2024, 2, 29), timestamp.date());
+        assert_eq!(45_296_789, timestamp.millis_of_day());
+
+        let before_epoch = TimestampMillis(-1);
+        assert_eq!(Date32(-1), before_epoch.date());
+        assert_eq!(86_399_999, before_epoch.millis_of_day());
+
+        let next = timestamp + Duration::from_days(1);
+        assert_eq!(Date32::from_ymd(2024, 3, 1), next.date());
+        assert_eq!(Duration::from_hours(24), next - timestamp);
+        assert_eq!(1, (next - timestamp).whole_days());
+        assert_eq!(Date32(1).to_timestamp(), TimestampMillis(86_400_000));
+    }
+
+    #[cfg(not(feature = "type-tags"))]
+    #[test]
+    fn time_types_with_alignment() {
+        let mut storage = [0u64; 32];
+        {
+            let mut writer = Writer::from(&mut storage);
+            writer.write_date32(Date32(-2));
+            writer.write_timestamp_millis(TimestampMillis(0x0102_0304_0506));
+            writer.copy_array_duration(&[Duration(-1)]);
+        }
+
+        let expected = [
+            254, 255, 255, 255, 0, 0, 0, 0, 6, 5, 4, 3, 2, 1, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 255,
+            255, 255, 255, 255, 255, 255, 255u8,
+        ];
+        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, 32) };
+        assert_eq!(&expected[..], actual);
+
+        let mut reader = Reader::from(&mut storage);
+        reader.reset();
+
+        assert_eq!(Date32(-2), reader.read_date32());
+        assert_eq!(
+            TimestampMillis(0x0102_0304_0506),
+            reader.read_timestamp_millis()
+        );
+        assert_eq!(&[Duration(-1)], reader.read_array_duration());
+    }
+
+    #[test]
+    fn date32_array() {
+        let mut storage = [0u64; 32];
+        {
+            let mut writer = Writer::from(&mut storage);
+            let days = writer.init_array_date32(3);
+            for (i, day) in days.iter_mut().enumerate() {
+                *day = Date32::from_ymd(2024, 12, 30).add_days(i as i32);
+            }
+        }
+
+        let mut reader = Reader::from(&mut storage);
+        reader.reset();
+
+        let days = reader.read_array_date32();
+        assert_eq!(3, days.len());
+        assert_eq!((2025, 1, 1), days[2].to_ymd());
+        assert_eq!(1, days[2].week_of_year());
+    }
+}
@@ -1,78 +1,555 @@
-static mut ERR_STORAGE: [u8; 256] = [0; 256];
+use core::fmt::{self, Write};
+use core::num::TryFromIntError;
+use core::panic::Location;
+use core::str::Utf8Error;
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+
+use super::region::{read_region, write_fields, RegionWriter, StaticRegion};
+use crate::conduit::{HeaderError, PayloadError};
+
+/// The size of the error region unless `setup_interop!` configures one.
+pub const DEFAULT_ERROR_SIZE: usize = 256;
+
+/// The space for an error's key-value fields, in bytes.
+pub const MAX_FIELD_BYTES: usize = 128;
+
+static mut ERR_STORAGE: [u8; DEFAULT_ERROR_SIZE] = [0; DEFAULT_ERROR_SIZE];
+static mut ERROR_REGION: Option<(*mut u8, usize)> = None;
+static mut ERROR_CODE: ErrorCode = ErrorCode::UNSPECIFIED;
+static mut FIELD_STORAGE: [u8; MAX_FIELD_BYTES] = [0; MAX_FIELD_BYTES];
+static mut FIELD_LENGTH: usize = 0;
 
 pub const OK: i32 = 0;
 pub const ERROR: i32 = 1;
+pub const PANIC: i32 = 2;
+
+/// A numeric error category the host can act on without matching messages.
+///
+/// Codes below 256 are reserved for `zaw`; modules define their own with
+/// [`ErrorCode::new`] from 256 upwards.
+///
+/// These are reported through `getErrorCode` and are separate from the
+/// [`OK`] / [`ERROR`] / [`PANIC`] values an export returns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ErrorCode(i32);
+
+impl ErrorCode {
+    /// No category was given.
+    pub const UNSPECIFIED: Self = Self(0);
+    /// The host sent a malformed or inconsistent payload.
+    pub const INVALID_INPUT: Self = Self(1);
+    /// A channel or other fixed-size storage was too small.
+    pub const CAPACITY_EXCEEDED: Self = Self(2);
+    /// A bug or broken invariant in the module.
+    pub const INTERNAL: Self = Self(3);
+    /// The module panicked. The call itself returns [`PANIC`], which is `2`.
+    pub const PANIC: Self = Self(4);
+
+    /// The first code available to modules.
+    pub const FIRST_CUSTOM: i32 = 256;
+
+    pub const fn new(code: i32) -> Self {
+        Self(code)
+    }
+
+    pub const fn value(self) -> i32 {
+        self.0
+    }
+}
 
 #[derive(Debug)]
 pub struct Error {
-    message: String,
+    message: Message,
+    code: ErrorCode,
+    has_fields: bool,
+}
+
+#[derive(Debug)]
+enum Message {
+    Static(&'static str),
+    #[cfg(feature = "alloc")]
+    Owned(String),
+    /// Already formatted into the error region.
+    Stored,
 }
 
 impl Error {
+    const fn with_message(message: Message) -> Self {
+        Self {
+            message,
+            code: ErrorCode::UNSPECIFIED,
+            has_fields: false,
+        }
+    }
+
+    #[cfg(feature = "alloc")]
     pub fn new(message: String) -> Self {
-        Self { message }
+        Self::with_message(Message::Owned(message))
+    }
+
+    /// Creates an error from a static message, without allocating.
+    pub const fn from_static(message: &'static str) -> Self {
+        Self::with_message(Message::Static(message))
+    }
+
+    /// Formats a message straight into the error region, without allocating.
+    ///
+    /// The message is formatted immediately, and the region holds a single
+    /// message, so creating another stored error overwrites this one's text:
+    /// both then report the second message. Return or [`store`](Self::store)
+    /// a stored error before creating the next.
+    pub fn stored(args: fmt::Arguments) -> Self {
+        write_error_fmt(args);
+
+        Self::with_message(Message::Stored)
+    }
+
+    /// Formats `err` into the error region, followed by the caller's location.
+    #[track_caller]
+    fn stored_at(err: impl fmt::Display, code: ErrorCode) -> Self {
+        let location = Location::caller();
+
+        Self::stored(format_args!(
+            "{}\n    at {}:{}",
+            err,
+            location.file(),
+            location.line()
+        ))
+        .with_code(code)
+    }
+
+    /// Sets the category reported to the host through `getErrorCode`.
+    pub const fn with_code(mut self, code: ErrorCode) -> Self {
+        self.code = code;
+        self
+    }
+
+    pub const fn code(&self) -> ErrorCode {
+        self.code
+    }
+
+    /// Attaches a key-value field, written after the message when the error is stored.
+    ///
+    /// The value is formatted straight into a static field buffer, so like
+    /// stored messages only one error's fields are held at a time: the first
+    /// field added to an error replaces any previous error's fields. A field
+    /// that doesn't fit is dropped whole, while later, shorter ones may still fit.
+    #[allow(static_mut_refs)]
+    pub fn with_field(mut self, key: &str, value: impl fmt::Display) -> Self {
+        unsafe {
+            if !self.has_fields {
+                FIELD_LENGTH = 0;
+                self.has_fields = true;
+            }
+
+            // Each field is null-terminated, so a full buffer takes no more
+            if FIELD_LENGTH < MAX_FIELD_BYTES - 1 {
+                let mut writer = RegionWriter::new(&mut FIELD_STORAGE[FIELD_LENGTH..]);
+                let _ = write!(writer, "{}={}", key, value);
+
+                // A cut short value would read as a real one
+                if !writer.is_truncated() {
+                    FIELD_LENGTH += writer.as_str().len() + 1;
+                }
+            }
+        }
+
+        self
+    }
+
+    pub fn message(&self) -> &str {
+        match &self.message {
+            Message::Static(message) => message,
+            #[cfg(feature = "alloc")]
+            Message::Owned(message) => message,
+            Message::Stored => stored_message(),
+        }
+    }
+
+    /// Adds a line describing what was being done when the error occurred,
+    /// followed by the caller's location.
+    ///
+    /// The context is appended to the message in the error region, so the
+    /// chain builds up without allocating. Context that doesn't fit in the
+    /// region is truncated.
+    #[track_caller]
+    pub fn context(self, context: &'static str) -> Self {
+        let location = Location::caller();
+
+        self.write_message();
+
+        let _ = write!(
+            RegionWriter::append(error_bytes()),
+            "\n{}\n    at {}:{}",
+            context,
+            location.file(),
+            location.line()
+        );
+
+        Self {
+            message: Message::Stored,
+            ..self
+        }
+    }
+
+    fn write_message(&self) {
+        match &self.message {
+            Message::Stored => {}
+            _ => write_error_to_storage(self.message()),
+        }
+    }
+
+    /// Writes the message and any fields to the error region, and records the
+    /// code, for the host to read.
+    #[allow(static_mut_refs)]
+    pub fn store(&self) {
+        self.write_message();
+
+        unsafe {
+            ERROR_CODE = self.code;
+
+            let fields = if self.has_fields {
+                &FIELD_STORAGE[..FIELD_LENGTH]
+            } else {
+                &[]
+            };
+            write_fields(error_bytes(), fields);
+        }
     }
 }
 
-impl std::fmt::Display for Error {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.message)
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
     }
 }
 
-impl std::error::Error for Error {}
+impl core::error::Error for Error {}
 
-pub type Result<T> = std::result::Result<T, Error>;
+impl From<HeaderError> for Error {
+    #[track_caller]
+    fn from(err: HeaderError) -> Self {
+        let error = Self::stored_at(err, ErrorCode::INVALID_INPUT);
+
+        match err {
+            HeaderError::BadMagic { found } => {
+                error.with_field("found", format_args!("0x{:08x}", found))
+            }
+            HeaderError::VersionMismatch { expected, found } => error
+                .with_field("expected", expected)
+                .with_field("found", found),
+            HeaderError::SchemaMismatch { expected, found } => error
+                .with_field("expected", expected)
+                .with_field("found", found),
+            HeaderError::PayloadTooLarge { length, capacity } => error
+                .with_field("length", length)
+                .with_field("capacity", capacity),
+            HeaderError::TypeTagsMismatch { expected, found } => error
+                .with_field("expected", expected)
+                .with_field("found", found),
+        }
+    }
+}
+
+impl From<PayloadError> for Error {
+    #[track_caller]
+    fn from(err: PayloadError) -> Self {
+        match err {
+            PayloadError::TooLarge { length, capacity } => {
+                Self::stored_at(err, ErrorCode::CAPACITY_EXCEEDED)
+                    .with_field("length", length)
+                    .with_field("capacity", capacity)
+            }
+            PayloadError::TrailingBytes { offset, remaining } => {
+                Self::stored_at(err, ErrorCode::INVALID_INPUT)
+                    .with_field("offset", offset)
+                    .with_field("remaining", remaining)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<crate::conduit::serde::Error> for Error {
+    #[track_caller]
+    fn from(err: crate::conduit::serde::Error) -> Self {
+        Self::stored_at(err, ErrorCode::INVALID_INPUT)
+    }
+}
+
+impl From<Utf8Error> for Error {
+    #[track_caller]
+    fn from(err: Utf8Error) -> Self {
+        Self::stored_at(
+            format_args!("Invalid UTF-8: {}", err),
+            ErrorCode::INVALID_INPUT,
+        )
+    }
+}
+
+impl From<TryFromIntError> for Error {
+    #[track_caller]
+    fn from(err: TryFromIntError) -> Self {
+        Self::stored_at(
+            format_args!("Integer conversion failed: {}", err),
+            ErrorCode::INVALID_INPUT,
+        )
+    }
+}
+
+/// Adds context to errors as they're propagated with `?`.
+///
+/// ```rust
+/// use zaw::interop::error::{Context, Result};
+///
+/// fn read_name(bytes: &[u8]) -> Result<&str> {
+///     let name = core::str::from_utf8(bytes).context("while reading the series name")?;
+///     Ok(name)
+/// }
+/// ```
+pub trait Context<T> {
+    /// Converts the error into an [`Error`], adding `context` and the caller's location.
+    #[track_caller]
+    fn context(self, context: &'static str) -> Result<T>;
+}
+
+impl<T, E> Context<T> for core::result::Result<T, E>
+where
+    Error: From<E>,
+{
+    #[track_caller]
+    fn context(self, context: &'static str) -> Result<T> {
+        match self {
+            Ok(value) => Ok(value),
+            Err(err) => Err(Error::from(err).context(context)),
+        }
+    }
+}
+
+impl<T> Context<T> for Option<T> {
+    /// Turns `None` into an error with `context` as its message.
+    #[track_caller]
+    fn context(self, context: &'static str) -> Result<T> {
+        match self {
+            Some(value) => Ok(value),
+            None => Err(Error::stored_at(context, ErrorCode::UNSPECIFIED)),
+        }
+    }
+}
+
+pub type Result<T> = core::result::Result<T, Error>;
 
 #[allow(static_mut_refs)]
+fn error_bytes() -> &'static mut [u8] {
+    unsafe {
+        match ERROR_REGION {
+            Some((ptr, len)) => core::slice::from_raw_parts_mut(ptr, len),
+            None => &mut ERR_STORAGE,
+        }
+    }
+}
+
+/// Uses statically reserved storage for the error region instead of the default.
+pub fn set_error_region<const SIZE: usize>(region: &'static StaticRegion<SIZE>) {
+    unsafe { ERROR_REGION = Some(region.as_raw()) }
+}
+
 pub fn get_error_ptr() -> i32 {
-    unsafe { ERR_STORAGE.as_ptr() as i32 }
+    error_bytes().as_ptr() as i32
 }
 
+/// Returns the size of the error region in bytes, including the null terminator.
+pub fn get_error_size() -> i32 {
+    error_bytes().len() as i32
+}
+
+/// Returns the code of the last error stored, or of the last panic.
 #[allow(static_mut_refs)]
+pub fn get_error_code() -> i32 {
+    unsafe { ERROR_CODE.value() }
+}
+
+/// Resets the error region to a single null byte and the code to
+/// [`ErrorCode::UNSPECIFIED`], as the protocol requires after a successful call.
+pub fn clear_error() {
+    error_bytes()[0] = 0;
+    set_error_code(ErrorCode::UNSPECIFIED);
+}
+
+/// Records the code reported by `getErrorCode`, for errors that bypass [`Error::store`].
+pub fn set_error_code(code: ErrorCode) {
+    unsafe { ERROR_CODE = code }
+}
+
+fn error_region() -> RegionWriter<'static> {
+    RegionWriter::new(error_bytes())
+}
+
+/// Returns the message currently held in the error region.
+pub fn stored_message() -> &'static str {
+    read_region(error_bytes())
+}
+
 pub fn write_error_to_storage(msg: &str) {
-    unsafe {
-        let bytes = msg.as_bytes();
-        let to_copy = bytes.len().min(ERR_STORAGE.len() - 1);
-        ERR_STORAGE[..to_copy].copy_from_slice(&bytes[..to_copy]);
-        ERR_STORAGE[to_copy] = 0; // Null terminate
-    }
+    let _ = error_region().write_str(msg);
+}
+
+/// Formats a message straight into the error region, truncating it if full.
+pub fn write_error_fmt(args: fmt::Arguments) {
+    let _ = error_region().write_fmt(args);
 }
 
+/// Runs an exported function, returning [`OK`] with the error region cleared,
+/// or [`ERROR`] with the error written to it.
+///
+/// The output channel is rewound first, so `getOutputLength` only counts what
+/// this call wrote, and the length from `setInputLength` is dropped once it
+/// returns, so it bounds the input for this call only.
+///
+/// Buffered log records are flushed before returning. With the `catch-unwind`
+/// feature, a panic returns [`PANIC`] instead of trapping, so the instance
+/// keeps serving calls.
 pub fn handle<F>(func: F) -> i32
 where
     F: FnOnce() -> Result<()>,
 {
-    match func() {
-        Ok(()) => OK,
+    super::panic::catch_panic(PANIC, || {
+        // Clear first too, so a trap that writes no message can't show a stale one
+        clear_error();
+        // Likewise the output, so a call that never writes it can't report the last call's length
+        super::reset_output();
+
+        let result = status_or_error(func());
+        // The input length only describes this call
+        super::end_call();
+
+        super::log::flush();
+
+        result
+    })
+}
+
+/// Converts a result to an export return value, clearing the error region,
+/// or writing the error to it and returning [`ERROR`].
+///
+/// Unlike [`handle`], this leaves the output channel and logs alone, for
+/// exports that configure the module between calls.
+pub(crate) fn status_or_error(result: Result<()>) -> i32 {
+    match result {
+        Ok(()) => {
+            clear_error();
+            OK
+        }
         Err(err) => {
-            write_error_to_storage(&err.to_string());
+            err.store();
             ERROR
         }
     }
 }
 
-// Error creation with location info for compatibility with tests
+// Error creation with location info for compatibility with tests, followed by any trace! frames.
+// The message is formatted straight into the error region, so no allocation is needed.
 #[macro_export]
 macro_rules! zaw_error {
     ($msg:expr) => {
-        $crate::interop::error::Error::new(format!("{}\n    at {}:{}", $msg, file!(), line!()))
+        $crate::interop::error::Error::stored(format_args!("{}\n    at {}:{}{}", $msg, file!(), line!(), $crate::interop::stack::frames()))
     };
     ($fmt:expr, $($arg:tt)*) => {
-        $crate::interop::error::Error::new(format!("{}\n    at {}:{}", format!($fmt, $($arg)*), file!(), line!()))
+        $crate::interop::error::Error::stored(format_args!("{}\n    at {}:{}{}", format_args!($fmt, $($arg)*), file!(), line!(), $crate::interop::stack::frames()))
     };
 }
 
 #[macro_export]
 macro_rules! zaw_panic {
     ($msg:expr) => {{
-        let msg = format!("{}\n    at {}:{}", $msg, file!(), line!());
-        $crate::interop::error::write_error_to_storage(&msg);
-        panic!("{}", msg)
+        $crate::interop::error::write_error_fmt(format_args!("{}\n    at {}:{}{}", $msg, file!(), line!(), $crate::interop::stack::frames()));
+        $crate::interop::panic::mark_message_stored();
+        panic!("{}", $crate::interop::error::stored_message())
     }};
     ($fmt:expr, $($arg:tt)*) => {{
-        let msg = format!("{}\n    at {}:{}", format!($fmt, $($arg)*), file!(), line!());
-        $crate::interop::error::write_error_to_storage(&msg);
-        panic!("{}", msg)
+        $crate::interop::error::write_error_fmt(format_args!("{}\n    at {}:{}{}", format_args!($fmt, $($arg)*), file!(), line!(), $crate::interop::stack::frames()));
+        $crate::interop::panic::mark_message_stored();
+        panic!("{}", $crate::interop::error::stored_message())
     }};
 }
+
+/// Returns early with a [`zaw_error!`](crate::zaw_error) built from the message.
+///
+/// ```rust
+/// # fn check(count: u32) -> zaw::interop::error::Result<()> {
+/// if count == 0 {
+///     zaw::zaw_bail!("Expected at least one value");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[macro_export]
+macro_rules! zaw_bail {
+    ($($arg:tt)+) => {
+        return ::core::result::Result::Err($crate::zaw_error!($($arg)+).into())
+    };
+}
+
+/// Returns early with an error naming the condition if it's false.
+///
+/// Use this to validate host input, which the module can't trust.
+///
+/// ```rust
+/// # fn check(a: &[f32], b: &[f32]) -> zaw::interop::error::Result<()> {
+/// zaw::zaw_ensure!(a.len() == b.len(), "Expected matching lengths, found {} and {}", a.len(), b.len());
+/// # Ok(())
+/// # }
+/// ```
+#[macro_export]
+macro_rules! zaw_ensure {
+    ($cond:expr $(,)?) => {
+        if !$cond {
+            $crate::zaw_bail!(concat!("Condition failed: `", stringify!($cond), "`"));
+        }
+    };
+    ($cond:expr, $($arg:tt)+) => {
+        if !$cond {
+            $crate::zaw_bail!("{}\n    condition: `{}`", format_args!($($arg)+), stringify!($cond));
+        }
+    };
+}
+
+/// Panics with a message naming the condition if it's false.
+///
+/// Use this for the module's own invariants; see [`zaw_ensure!`](crate::zaw_ensure)
+/// for host input.
+#[macro_export]
+macro_rules! zaw_assert {
+    ($cond:expr $(,)?) => {
+        if !$cond {
+            $crate::zaw_panic!(concat!("Assertion failed: `", stringify!($cond), "`"));
+        }
+    };
+    ($cond:expr, $($arg:tt)+) => {
+        if !$cond {
+            $crate::zaw_panic!("{}\n    assertion: `{}`", format_args!($($arg)+), stringify!($cond));
+        }
+    };
+}
+
+/// Like [`zaw_ensure!`](crate::zaw_ensure), but only checked in debug builds.
+#[macro_export]
+macro_rules! zaw_debug_ensure {
+    ($($arg:tt)+) => {
+        if cfg!(debug_assertions) {
+            $crate::zaw_ensure!($($arg)+);
+        }
+    };
+}
+
+/// Like [`zaw_assert!`](crate::zaw_assert), but only checked in debug builds.
+#[macro_export]
+macro_rules! zaw_debug_assert {
+    ($($arg:tt)+) => {
+        if cfg!(debug_assertions) {
+            $crate::zaw_assert!($($arg)+);
+        }
+    };
+}
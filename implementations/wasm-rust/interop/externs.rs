@@ -1,3 +1,58 @@
+#[cfg(target_arch = "wasm32")]
 extern "C" {
-    pub fn hostLog();
+    /// Asks the host to read the log region, passing the message's [`Level`](super::log::Level).
+    pub fn hostLog(level: i32);
+}
+
+/// Native builds, such as tests, have no host to log to.
+///
+/// # Safety
+///
+/// Always safe; it's only `unsafe` to match the WASM import.
+#[cfg(all(not(target_arch = "wasm32"), not(test)))]
+#[allow(non_snake_case)]
+pub unsafe fn hostLog(_level: i32) {}
+
+#[cfg(all(not(target_arch = "wasm32"), test))]
+std::thread_local! {
+    /// The level and message of every immediate `hostLog` call made by the current test.
+    pub static HOST_LOGS: core::cell::RefCell<std::vec::Vec<(i32, std::string::String)>> =
+        const { core::cell::RefCell::new(std::vec::Vec::new()) };
+
+    /// The level, including any [`LOG_CONTINUED`](super::log::LOG_CONTINUED) flag,
+    /// sequence number and message of each record, for every batch sent by the
+    /// current test.
+    #[allow(clippy::type_complexity)]
+    pub static HOST_BATCHES: core::cell::RefCell<
+        std::vec::Vec<std::vec::Vec<(i32, u32, std::string::String)>>,
+    > = const { core::cell::RefCell::new(std::vec::Vec::new()) };
+}
+
+/// Records the call in [`HOST_LOGS`] or [`HOST_BATCHES`] in place of a host.
+///
+/// # Safety
+///
+/// Always safe; it's only `unsafe` to match the WASM import.
+#[cfg(all(not(target_arch = "wasm32"), test))]
+#[allow(non_snake_case)]
+pub unsafe fn hostLog(level: i32) {
+    use super::log;
+
+    if level == log::LOG_BATCH {
+        let records = log::read_log_batch()
+            .map(|record| {
+                let flags = if record.continued {
+                    log::LOG_CONTINUED
+                } else {
+                    0
+                };
+                let level = (record.level as u8 | flags) as i32;
+                (level, record.sequence, record.message.into())
+            })
+            .collect();
+        HOST_BATCHES.with(|batches| batches.borrow_mut().push(records));
+    } else {
+        let message = log::read_log().into();
+        HOST_LOGS.with(|logs| logs.borrow_mut().push((level, message)));
+    }
 }
@@ -0,0 +1,228 @@
+use alloc::alloc::{alloc, dealloc, realloc};
+use core::alloc::Layout;
+
+use super::error::{self, Error, ErrorCode};
+use super::{channel_layout, ptr_or_error, INPUT, INPUT_LENGTH, OUTPUT};
+use crate::conduit::{Reader, Writer};
+
+/// A heap allocation backing a channel.
+pub(super) struct Buffer {
+    pub(super) ptr: *mut u64,
+    pub(super) layout: Layout,
+}
+
+impl Buffer {
+    fn len_in_u64s(&self) -> usize {
+        self.layout.size() / 8
+    }
+}
+
+pub(super) static mut INPUT_BUFFER: Option<Buffer> = None;
+pub(super) static mut OUTPUT_BUFFER: Option<Buffer> = None;
+
+fn allocate_buffer(size_in_bytes: i32) -> error::Result<Buffer> {
+    let layout = channel_layout(size_in_bytes)?;
+
+    unsafe {
+        let ptr = alloc(layout) as *mut u64;
+        if ptr.is_null() {
+            return Err(Error::stored(format_args!(
+                "Failed to allocate {} bytes of channel storage",
+                layout.size()
+            ))
+            .with_code(ErrorCode::CAPACITY_EXCEEDED));
+        }
+
+        // Touch the memory to force WASM runtime to allocate pages
+        core::ptr::write_bytes(ptr, 0, layout.size() / 8);
+
+        Ok(Buffer { ptr, layout })
+    }
+}
+
+/// Resizes a buffer, preserving its contents and zeroing any new space.
+///
+/// On failure the original buffer is returned alongside the error, still valid.
+fn reallocate_buffer(
+    buffer: Buffer,
+    size_in_bytes: i32,
+) -> core::result::Result<Buffer, (Buffer, Error)> {
+    let layout = match channel_layout(size_in_bytes) {
+        Ok(layout) => layout,
+        Err(err) => return Err((buffer, err)),
+    };
+
+    unsafe {
+        let ptr = realloc(buffer.ptr as *mut u8, buffer.layout, layout.size()) as *mut u64;
+        if ptr.is_null() {
+            let err = Error::stored(format_args!(
+                "Failed to reallocate {} bytes of channel storage",
+                layout.size()
+            ))
+            .with_code(ErrorCode::CAPACITY_EXCEEDED);
+            return Err((buffer, err));
+        }
+
+        let old_size = buffer.layout.size();
+        if layout.size() > old_size {
+            core::ptr::write_bytes((ptr as *mut u8).add(old_size), 0, layout.size() - old_size);
+        }
+
+        Ok(Buffer { ptr, layout })
+    }
+}
+
+fn free_buffer(buffer: Buffer) {
+    unsafe { dealloc(buffer.ptr as *mut u8, buffer.layout) }
+}
+
+#[allow(static_mut_refs)]
+unsafe fn bind_input(buffer: Buffer) -> *mut u64 {
+    let ptr = buffer.ptr;
+    let slice = core::slice::from_raw_parts_mut(ptr, buffer.len_in_u64s());
+
+    INPUT = Some(Reader::from(slice));
+    INPUT_BUFFER = Some(buffer);
+    INPUT_LENGTH = None;
+
+    ptr
+}
+
+#[allow(static_mut_refs)]
+unsafe fn bind_output(buffer: Buffer) -> *mut u64 {
+    let ptr = buffer.ptr;
+    let slice = core::slice::from_raw_parts_mut(ptr, buffer.len_in_u64s());
+
+    OUTPUT = Some(Writer::from(slice));
+    OUTPUT_BUFFER = Some(buffer);
+
+    ptr
+}
+
+#[allow(static_mut_refs)]
+unsafe fn try_allocate_input(size_in_bytes: i32) -> error::Result<*mut u64> {
+    let buffer = allocate_buffer(size_in_bytes)?;
+
+    INPUT = None;
+    free_input_buffer();
+
+    Ok(bind_input(buffer))
+}
+
+#[allow(static_mut_refs)]
+unsafe fn try_allocate_output(size_in_bytes: i32) -> error::Result<*mut u64> {
+    let buffer = allocate_buffer(size_in_bytes)?;
+
+    OUTPUT = None;
+    free_output_buffer();
+
+    Ok(bind_output(buffer))
+}
+
+#[allow(static_mut_refs)]
+unsafe fn try_reallocate_input(size_in_bytes: i32) -> error::Result<*mut u64> {
+    let Some(old) = INPUT_BUFFER.take() else {
+        return try_allocate_input(size_in_bytes);
+    };
+
+    INPUT = None;
+    match reallocate_buffer(old, size_in_bytes) {
+        Ok(buffer) => Ok(bind_input(buffer)),
+        Err((old, err)) => {
+            bind_input(old);
+            Err(err)
+        }
+    }
+}
+
+#[allow(static_mut_refs)]
+unsafe fn try_reallocate_output(size_in_bytes: i32) -> error::Result<*mut u64> {
+    let Some(old) = OUTPUT_BUFFER.take() else {
+        return try_allocate_output(size_in_bytes);
+    };
+
+    OUTPUT = None;
+    match reallocate_buffer(old, size_in_bytes) {
+        Ok(buffer) => Ok(bind_output(buffer)),
+        Err((old, err)) => {
+            bind_output(old);
+            Err(err)
+        }
+    }
+}
+
+/// Allocates the input channel, freeing any previous allocation.
+///
+/// The size is rounded up to a multiple of 16 bytes.
+///
+/// # Returns
+///
+/// A pointer to the channel storage, or `0` with a message in the error region
+/// if the size is invalid or the allocation fails.
+pub fn allocate_input_channel(size_in_bytes: i32) -> i32 {
+    ptr_or_error(unsafe { try_allocate_input(size_in_bytes) })
+}
+
+/// Allocates the output channel, freeing any previous allocation.
+///
+/// The size is rounded up to a multiple of 16 bytes.
+///
+/// # Returns
+///
+/// A pointer to the channel storage, or `0` with a message in the error region
+/// if the size is invalid or the allocation fails.
+pub fn allocate_output_channel(size_in_bytes: i32) -> i32 {
+    ptr_or_error(unsafe { try_allocate_output(size_in_bytes) })
+}
+
+/// Resizes the input channel, preserving its contents.
+///
+/// The channel may move, so hosts must rebind their views using the returned
+/// pointer. Allocates the channel if it doesn't exist yet.
+///
+/// # Returns
+///
+/// A pointer to the channel storage, or `0` with a message in the error region
+/// if the size is invalid or the allocation fails. On failure the existing
+/// channel is left unchanged.
+pub fn reallocate_input_channel(size_in_bytes: i32) -> i32 {
+    ptr_or_error(unsafe { try_reallocate_input(size_in_bytes) })
+}
+
+/// Resizes the output channel, preserving its contents.
+///
+/// The channel may move, so hosts must rebind their views using the returned
+/// pointer. Allocates the channel if it doesn't exist yet.
+///
+/// # Returns
+///
+/// A pointer to the channel storage, or `0` with a message in the error region
+/// if the size is invalid or the allocation fails. On failure the existing
+/// channel is left unchanged.
+pub fn reallocate_output_channel(size_in_bytes: i32) -> i32 {
+    ptr_or_error(unsafe { try_reallocate_output(size_in_bytes) })
+}
+
+/// Frees the heap allocated input channel, if any.
+///
+/// # Safety
+///
+/// The input channel must already be unbound.
+#[allow(static_mut_refs)]
+pub(super) unsafe fn free_input_buffer() {
+    if let Some(buffer) = INPUT_BUFFER.take() {
+        free_buffer(buffer);
+    }
+}
+
+/// Frees the heap allocated output channel, if any.
+///
+/// # Safety
+///
+/// The output channel must already be unbound.
+#[allow(static_mut_refs)]
+pub(super) unsafe fn free_output_buffer() {
+    if let Some(buffer) = OUTPUT_BUFFER.take() {
+        free_buffer(buffer);
+    }
+}
@@ -0,0 +1,141 @@
+use super::chunk::{Sink, LOG_CONTINUED};
+use super::Level;
+use crate::interop::externs;
+
+/// The `hostLog` level that marks a batch of buffered records in the log region.
+pub const LOG_BATCH: i32 = 0xff;
+
+/// The size of a record header: level `u8`, sequence `u32` and length `u32`, little endian.
+pub const RECORD_HEADER_SIZE: usize = 9;
+
+/// Room for the longest UTF-8 character, so every chunk makes progress.
+const MIN_RECORD_SPACE: usize = 4;
+
+/// Accumulates log records in the log region, to be sent in one `hostLog` call.
+///
+/// Records are laid out back to back, each a [`RECORD_HEADER_SIZE`] byte header
+/// followed by its UTF-8 message, and the batch ends with a zero level byte.
+pub(super) struct Batch {
+    len: usize,
+}
+
+impl Batch {
+    pub const fn new() -> Self {
+        Self { len: 0 }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Sends the batch to the host, if it holds any records.
+    pub fn flush(&mut self) {
+        if !self.is_empty() {
+            self.len = 0;
+            unsafe { externs::hostLog(LOG_BATCH) }
+        }
+    }
+}
+
+/// Appends one message to a [`Batch`] as a [`Sink`], flushing the batch
+/// whenever the region fills up.
+///
+/// A message is only split across records, flagged with [`LOG_CONTINUED`],
+/// when it doesn't fit in the region on its own.
+pub(super) struct BatchSink<'a> {
+    batch: &'a mut Batch,
+    region: &'a mut [u8],
+    level: Level,
+    sequence: u32,
+}
+
+impl<'a> BatchSink<'a> {
+    pub fn new(batch: &'a mut Batch, region: &'a mut [u8], level: Level, sequence: u32) -> Self {
+        // Leave room for the batch's terminator too
+        if batch.len + RECORD_HEADER_SIZE + MIN_RECORD_SPACE >= region.len() {
+            region[batch.len] = 0;
+            batch.flush();
+        }
+
+        Self {
+            batch,
+            region,
+            level,
+            sequence,
+        }
+    }
+
+    fn write_record(&mut self, len: usize, continued: bool) {
+        let start = self.batch.len + RECORD_HEADER_SIZE;
+        let flags = if continued { LOG_CONTINUED } else { 0 };
+
+        let header = &mut self.region[self.batch.len..start];
+        header[0] = self.level as u8 | flags;
+        header[1..5].copy_from_slice(&self.sequence.to_le_bytes());
+        header[5..9].copy_from_slice(&(len as u32).to_le_bytes());
+
+        self.batch.len = start + len;
+        self.region[self.batch.len] = 0;
+    }
+}
+
+impl Sink for BatchSink<'_> {
+    fn space(&mut self) -> &mut [u8] {
+        let end = self.region.len() - 1;
+        &mut self.region[self.batch.len + RECORD_HEADER_SIZE..end]
+    }
+
+    fn overflow(&mut self, len: usize) -> usize {
+        if self.batch.is_empty() {
+            self.write_record(len, true);
+            self.batch.flush();
+            return 0;
+        }
+
+        // Send the earlier records and carry on at the start of the region
+        let start = self.batch.len + RECORD_HEADER_SIZE;
+        self.region[self.batch.len] = 0;
+        self.batch.flush();
+        self.region
+            .copy_within(start..start + len, RECORD_HEADER_SIZE);
+
+        len
+    }
+
+    fn finish(&mut self, len: usize) {
+        self.write_record(len, false);
+    }
+}
+
+/// A log record read back from a batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Record<'a> {
+    pub level: Level,
+    pub sequence: u32,
+    /// `true` if the message continues in the next record.
+    pub continued: bool,
+    pub message: &'a str,
+}
+
+/// Reads the records of a batch from the log region, as the host does.
+pub fn read_batch(region: &[u8]) -> impl Iterator<Item = Record<'_>> {
+    let mut pos = 0;
+
+    core::iter::from_fn(move || {
+        let header = region.get(pos..pos + RECORD_HEADER_SIZE)?;
+        let level = Level::from_u8(header[0] & !LOG_CONTINUED)?;
+        let sequence = u32::from_le_bytes(header[1..5].try_into().unwrap());
+        let length = u32::from_le_bytes(header[5..9].try_into().unwrap()) as usize;
+
+        let start = pos + RECORD_HEADER_SIZE;
+        let message = core::str::from_utf8(region.get(start..start + length)?).ok()?;
+        pos = start + length;
+
+        Some(Record {
+            level,
+            sequence,
+            continued: header[0] & LOG_CONTINUED != 0,
+            message,
+        })
+    })
+}
@@ -0,0 +1,136 @@
+use core::fmt;
+
+use super::Level;
+use crate::interop::externs;
+use crate::interop::region::TRUNCATION_MARKER;
+
+/// Set on a `hostLog` level, or a buffered record's level, when the message
+/// continues in the next chunk.
+pub const LOG_CONTINUED: u8 = 0x80;
+
+/// Where a [`ChunkWriter`] puts a message, one chunk at a time.
+pub(super) trait Sink {
+    /// Returns the space for the current chunk, which always fits a character.
+    fn space(&mut self) -> &mut [u8];
+
+    /// Makes room once the first `len` bytes of the space are full, usually by
+    /// sending them as a continued chunk.
+    ///
+    /// # Returns
+    ///
+    /// The number of those bytes now at the start of the new space.
+    fn overflow(&mut self, len: usize) -> usize;
+
+    /// Sends the first `len` bytes of the space as the message's last chunk.
+    fn finish(&mut self, len: usize);
+}
+
+/// Streams formatted text into a [`Sink`], splitting it into chunks at
+/// character boundaries rather than truncating it.
+///
+/// Text beyond `max` bytes is dropped, and the message ends with [`TRUNCATION_MARKER`].
+pub(super) struct ChunkWriter<S: Sink> {
+    sink: S,
+    len: usize,
+    total: usize,
+    max: usize,
+    truncated: bool,
+}
+
+impl<S: Sink> ChunkWriter<S> {
+    pub fn new(sink: S, max: usize) -> Self {
+        Self {
+            sink,
+            len: 0,
+            total: 0,
+            max,
+            truncated: false,
+        }
+    }
+
+    /// Sends the last chunk.
+    pub fn finish(mut self) {
+        self.sink.finish(self.len);
+    }
+
+    fn push(&mut self, mut s: &str) {
+        while !s.is_empty() {
+            let space = self.sink.space();
+            let mut count = s.len().min(space.len() - self.len);
+            while !s.is_char_boundary(count) {
+                count -= 1;
+            }
+
+            if count == 0 {
+                // The next character doesn't fit in this chunk
+                self.len = self.sink.overflow(self.len);
+                continue;
+            }
+
+            space[self.len..self.len + count].copy_from_slice(&s.as_bytes()[..count]);
+            self.len += count;
+            s = &s[count..];
+        }
+    }
+}
+
+impl<S: Sink> fmt::Write for ChunkWriter<S> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if self.truncated {
+            return Ok(());
+        }
+
+        let remaining = self.max - self.total;
+
+        if s.len() <= remaining {
+            self.push(s);
+            self.total += s.len();
+        } else {
+            let mut count = remaining;
+            while !s.is_char_boundary(count) {
+                count -= 1;
+            }
+
+            self.push(&s[..count]);
+            self.push(TRUNCATION_MARKER);
+            self.total = self.max;
+            self.truncated = true;
+        }
+
+        Ok(())
+    }
+}
+
+/// Sends each chunk of one message straight to the host.
+pub(super) struct HostSink<'a> {
+    region: &'a mut [u8],
+    level: Level,
+}
+
+impl<'a> HostSink<'a> {
+    pub fn new(region: &'a mut [u8], level: Level) -> Self {
+        Self { region, level }
+    }
+
+    fn send(&mut self, len: usize, flags: u8) {
+        self.region[len] = 0;
+        unsafe { externs::hostLog((self.level as u8 | flags) as i32) }
+    }
+}
+
+impl Sink for HostSink<'_> {
+    fn space(&mut self) -> &mut [u8] {
+        // Leave space for the null terminator
+        let end = self.region.len() - 1;
+        &mut self.region[..end]
+    }
+
+    fn overflow(&mut self, len: usize) -> usize {
+        self.send(len, LOG_CONTINUED);
+        0
+    }
+
+    fn finish(&mut self, len: usize) {
+        self.send(len, 0);
+    }
+}
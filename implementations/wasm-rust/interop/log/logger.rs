@@ -0,0 +1,66 @@
+use ::log::{Log, Metadata, Record, SetLoggerError};
+
+use super::{log_enabled, log_fmt_at, log_level, Level, LevelFilter, STATIC_MAX_LEVEL};
+
+/// Sends records from the `log` crate to the host through `hostLog`,
+/// formatted straight into the log region.
+///
+/// Each record is filtered by the runtime level set through `setLogLevel`.
+pub struct ZawLogger;
+
+static LOGGER: ZawLogger = ZawLogger;
+
+fn to_level(level: ::log::Level) -> Level {
+    match level {
+        ::log::Level::Error => Level::Error,
+        ::log::Level::Warn => Level::Warn,
+        ::log::Level::Info => Level::Info,
+        ::log::Level::Debug => Level::Debug,
+        ::log::Level::Trace => Level::Trace,
+    }
+}
+
+fn to_level_filter(filter: LevelFilter) -> ::log::LevelFilter {
+    match filter {
+        LevelFilter::Off => ::log::LevelFilter::Off,
+        LevelFilter::Error => ::log::LevelFilter::Error,
+        LevelFilter::Warn => ::log::LevelFilter::Warn,
+        LevelFilter::Info => ::log::LevelFilter::Info,
+        LevelFilter::Debug => ::log::LevelFilter::Debug,
+        LevelFilter::Trace => ::log::LevelFilter::Trace,
+    }
+}
+
+impl Log for ZawLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        log_enabled(to_level(metadata.level()))
+    }
+
+    fn log(&self, record: &Record) {
+        log_fmt_at(
+            to_level(record.level()),
+            format_args!("[{}] {}", record.target(), record.args()),
+        );
+    }
+
+    fn flush(&self) {
+        super::flush();
+    }
+}
+
+/// Installs [`ZawLogger`] as the `log` crate's logger.
+///
+/// Call this once, before any logging. The `log` crate's max level then
+/// follows the level the host sets through `setLogLevel`.
+pub fn init_logger() -> Result<(), SetLoggerError> {
+    ::log::set_logger(&LOGGER)?;
+    sync_max_level(log_level());
+
+    Ok(())
+}
+
+/// Keeps the `log` crate's max level in step with ours, so its macros skip
+/// disabled records before formatting them.
+pub(super) fn sync_max_level(filter: LevelFilter) {
+    ::log::set_max_level(to_level_filter(filter.min(STATIC_MAX_LEVEL)));
+}
@@ -0,0 +1,346 @@
+use core::fmt::{self, Write};
+use core::sync::atomic::{AtomicU32, AtomicU8, AtomicUsize, Ordering};
+
+use super::error::{self, Error, ErrorCode};
+use super::region::{read_region, StaticRegion};
+
+mod buffer;
+mod chunk;
+#[cfg(feature = "log")]
+mod logger;
+
+pub use buffer::{read_batch, Record, LOG_BATCH, RECORD_HEADER_SIZE};
+pub use chunk::LOG_CONTINUED;
+
+#[cfg(feature = "log")]
+pub use logger::{init_logger, ZawLogger};
+
+/// The size of the log region unless `setup_interop!` configures one.
+pub const DEFAULT_LOG_SIZE: usize = 1024;
+
+static mut LOG_STORAGE: [u8; DEFAULT_LOG_SIZE] = [0; DEFAULT_LOG_SIZE];
+static mut LOG_REGION: Option<(*mut u8, usize)> = None;
+
+#[allow(static_mut_refs)]
+fn log_bytes() -> &'static mut [u8] {
+    unsafe {
+        match LOG_REGION {
+            Some((ptr, len)) => core::slice::from_raw_parts_mut(ptr, len),
+            None => &mut LOG_STORAGE,
+        }
+    }
+}
+
+/// Uses statically reserved storage for the log region instead of the default.
+pub fn set_log_region<const SIZE: usize>(region: &'static StaticRegion<SIZE>) {
+    unsafe { LOG_REGION = Some(region.as_raw()) }
+}
+
+pub fn get_log_ptr() -> i32 {
+    log_bytes().as_ptr() as i32
+}
+
+/// Returns the size of the log region in bytes, including the null terminator.
+pub fn get_log_size() -> i32 {
+    log_bytes().len() as i32
+}
+
+/// Returns the message currently held in the log region.
+pub fn read_log() -> &'static str {
+    read_region(log_bytes())
+}
+
+/// Returns the records of the batch currently held in the log region.
+pub fn read_log_batch() -> impl Iterator<Item = Record<'static>> {
+    read_batch(log_bytes())
+}
+
+/// The severity of a log message, passed to `hostLog` as its numeric value.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Level {
+    Error = 1,
+    Warn = 2,
+    Info = 3,
+    Debug = 4,
+    Trace = 5,
+}
+
+impl Level {
+    pub const fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            1 => Some(Level::Error),
+            2 => Some(Level::Warn),
+            3 => Some(Level::Info),
+            4 => Some(Level::Debug),
+            5 => Some(Level::Trace),
+            _ => None,
+        }
+    }
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Level::Error => "error",
+            Level::Warn => "warn",
+            Level::Info => "info",
+            Level::Debug => "debug",
+            Level::Trace => "trace",
+        }
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The most verbose level logged, or `Off` to log nothing.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LevelFilter {
+    Off = 0,
+    Error = 1,
+    Warn = 2,
+    Info = 3,
+    Debug = 4,
+    Trace = 5,
+}
+
+impl LevelFilter {
+    pub const fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(LevelFilter::Off),
+            1 => Some(LevelFilter::Error),
+            2 => Some(LevelFilter::Warn),
+            3 => Some(LevelFilter::Info),
+            4 => Some(LevelFilter::Debug),
+            5 => Some(LevelFilter::Trace),
+            _ => None,
+        }
+    }
+
+    pub const fn allows(self, level: Level) -> bool {
+        level as u8 <= self as u8
+    }
+}
+
+/// The most verbose level compiled in, set by the `max-level-*` features.
+///
+/// Logging above this level compiles away, whatever the host asks for.
+pub const STATIC_MAX_LEVEL: LevelFilter = if cfg!(feature = "max-level-off") {
+    LevelFilter::Off
+} else if cfg!(feature = "max-level-error") {
+    LevelFilter::Error
+} else if cfg!(feature = "max-level-warn") {
+    LevelFilter::Warn
+} else if cfg!(feature = "max-level-info") {
+    LevelFilter::Info
+} else if cfg!(feature = "max-level-debug") {
+    LevelFilter::Debug
+} else {
+    LevelFilter::Trace
+};
+
+/// The level the host has asked for, until it calls `setLogLevel`.
+pub const DEFAULT_LOG_LEVEL: LevelFilter = LevelFilter::Info;
+
+static LOG_LEVEL: AtomicU8 = AtomicU8::new(DEFAULT_LOG_LEVEL as u8);
+
+/// Returns the most verbose level currently logged.
+pub fn log_level() -> LevelFilter {
+    LevelFilter::from_u8(LOG_LEVEL.load(Ordering::Relaxed)).unwrap_or(DEFAULT_LOG_LEVEL)
+}
+
+/// Sets the most verbose level logged at runtime, within [`STATIC_MAX_LEVEL`].
+pub fn set_max_level(filter: LevelFilter) {
+    LOG_LEVEL.store(filter as u8, Ordering::Relaxed);
+
+    #[cfg(feature = "log")]
+    logger::sync_max_level(filter);
+}
+
+/// Sets the runtime log level from the host, backing the `setLogLevel` export.
+///
+/// Unlike exports wrapped in [`handle`](error::handle), this keeps the output
+/// and logs of the previous call, so the host can change the level between
+/// a call and reading its results.
+///
+/// # Returns
+///
+/// [`OK`](error::OK), or [`error::ERROR`] if `level` isn't between `0` (off)
+/// and `5` (trace).
+pub fn set_log_level(level: i32) -> i32 {
+    let filter = u8::try_from(level)
+        .ok()
+        .and_then(LevelFilter::from_u8)
+        .ok_or_else(|| {
+            Error::stored(format_args!("Invalid log level {}", level))
+                .with_code(ErrorCode::INVALID_INPUT)
+        });
+
+    error::status_or_error(filter.map(set_max_level))
+}
+
+/// Returns `true` if a message at `level` would be sent to the host.
+#[inline]
+pub fn log_enabled(level: Level) -> bool {
+    STATIC_MAX_LEVEL.allows(level) && log_level().allows(level)
+}
+
+/// How log messages reach the host.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogMode {
+    /// Each message is sent with its own `hostLog` call.
+    Immediate,
+    /// Messages accumulate in the log region and are sent together with one
+    /// `hostLog(LOG_BATCH)` call by [`flush`], which [`handle`](error::handle)
+    /// calls at the end of each export, or when the region fills up.
+    Buffered,
+}
+
+static mut LOG_MODE: LogMode = LogMode::Immediate;
+static mut BATCH: buffer::Batch = buffer::Batch::new();
+static SEQUENCE: AtomicU32 = AtomicU32::new(0);
+
+#[allow(static_mut_refs)]
+pub fn log_mode() -> LogMode {
+    unsafe { LOG_MODE }
+}
+
+/// Switches between immediate and buffered logging, flushing any buffered records.
+///
+/// Hosts must understand batches before a module buffers its logs.
+pub fn set_log_mode(mode: LogMode) {
+    flush();
+
+    unsafe { LOG_MODE = mode }
+}
+
+/// Sends any buffered records to the host in one `hostLog` call.
+#[allow(static_mut_refs)]
+pub fn flush() {
+    unsafe { BATCH.flush() }
+}
+
+/// The longest message sent to the host unless [`set_max_log_length`] changes it.
+pub const DEFAULT_MAX_LOG_LENGTH: usize = 64 * 1024;
+
+static MAX_LOG_LENGTH: AtomicUsize = AtomicUsize::new(DEFAULT_MAX_LOG_LENGTH);
+
+/// Returns the most bytes of a message sent to the host.
+pub fn max_log_length() -> usize {
+    MAX_LOG_LENGTH.load(Ordering::Relaxed)
+}
+
+/// Sets the most bytes of a message sent to the host.
+///
+/// Longer messages are cut at a character boundary and end with
+/// [`TRUNCATION_MARKER`](super::region::TRUNCATION_MARKER).
+pub fn set_max_log_length(bytes: usize) {
+    MAX_LOG_LENGTH.store(bytes, Ordering::Relaxed);
+}
+
+/// Sends a message to the host, or buffers it, regardless of level.
+///
+/// Messages longer than the log region are split into chunks flagged with
+/// [`LOG_CONTINUED`], which the host joins back together.
+#[allow(static_mut_refs)]
+fn emit(level: Level, args: fmt::Arguments) {
+    let sequence = SEQUENCE.fetch_add(1, Ordering::Relaxed);
+
+    match log_mode() {
+        LogMode::Immediate => {
+            let sink = chunk::HostSink::new(log_bytes(), level);
+            write_chunked(sink, args);
+        }
+        LogMode::Buffered => {
+            let sink = unsafe { buffer::BatchSink::new(&mut BATCH, log_bytes(), level, sequence) };
+            write_chunked(sink, args);
+        }
+    }
+}
+
+fn write_chunked(sink: impl chunk::Sink, args: fmt::Arguments) {
+    let mut writer = chunk::ChunkWriter::new(sink, max_log_length());
+    let _ = writer.write_fmt(args);
+    writer.finish();
+}
+
+/// Sends a message to the host at `level`, if that level is enabled.
+pub fn log_at(level: Level, msg: &str) {
+    if log_enabled(level) {
+        emit(level, format_args!("{}", msg));
+    }
+}
+
+/// Formats a message straight into the log region and sends it to the host
+/// at `level`, if that level is enabled.
+///
+/// Messages longer than the region are sent in chunks. Use [`zaw_log!`](crate::zaw_log)
+/// rather than calling this directly, so disabled messages aren't formatted.
+pub fn log_fmt_at(level: Level, args: fmt::Arguments) {
+    if log_enabled(level) {
+        emit(level, args);
+    }
+}
+
+/// Sends a message to the host at [`Level::Info`].
+pub fn log(msg: &str) {
+    log_at(Level::Info, msg);
+}
+
+/// Formats a message straight into the log region and sends it to the host
+/// at [`Level::Info`].
+///
+/// Messages longer than the region are sent in chunks. Use [`zaw_logf!`](crate::zaw_logf)
+/// rather than calling this directly.
+pub fn log_fmt(args: fmt::Arguments) {
+    log_fmt_at(Level::Info, args);
+}
+
+/// Logs each entry of a channel journal, prefixed with `label`.
+#[cfg(feature = "journal")]
+pub fn log_journal(label: &str, journal: &crate::conduit::Journal) {
+    if journal.dropped() > 0 {
+        log_fmt(format_args!(
+            "{}: {} earlier entries dropped",
+            label,
+            journal.dropped()
+        ));
+    }
+    for entry in journal.iter() {
+        log_fmt(format_args!("{}: {}", label, entry));
+    }
+}
+
+/// Formats a log message straight into the log region at [`Level::Info`],
+/// without allocating.
+///
+/// ```rust,ignore
+/// zaw::zaw_logf!("processed {} items in {}ms", count, elapsed);
+/// ```
+#[macro_export]
+macro_rules! zaw_logf {
+    ($($arg:tt)*) => {
+        $crate::interop::log::log_fmt(format_args!($($arg)*))
+    };
+}
+
+/// Logs a formatted message at a [`Level`], skipping the formatting entirely
+/// when the level is disabled.
+///
+/// ```rust,ignore
+/// use zaw::interop::log::Level;
+///
+/// zaw::zaw_log!(Level::Debug, "bucket {} holds {} values", index, count);
+/// ```
+#[macro_export]
+macro_rules! zaw_log {
+    ($level:expr, $($arg:tt)+) => {{
+        let level = $level;
+        if $crate::interop::log::log_enabled(level) {
+            $crate::interop::log::log_fmt_at(level, format_args!($($arg)+));
+        }
+    }};
+}
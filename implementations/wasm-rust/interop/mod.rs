@@ -1,15 +1,38 @@
+use core::alloc::Layout;
+use core::cell::UnsafeCell;
+
 use crate::conduit::{Reader, Writer};
 
 pub mod error;
 pub mod externs;
+#[cfg(feature = "alloc")]
+mod heap;
 pub mod log;
+pub mod panic;
+mod region;
+pub mod stack;
 
-pub use error::{Error, OK};
-pub use log::log;
+pub use self::log::log;
+pub use error::{Error, ErrorCode, OK};
+#[cfg(feature = "alloc")]
+pub use heap::{
+    allocate_input_channel, allocate_output_channel, reallocate_input_channel,
+    reallocate_output_channel,
+};
+pub use region::{RegionWriter, StaticRegion, MIN_REGION_SIZE, TRUNCATION_MARKER};
 
 static mut INPUT: Option<Reader<'static>> = None;
 static mut OUTPUT: Option<Writer<'static>> = None;
+static mut INPUT_LENGTH: Option<u32> = None;
+
+/// The byte written over the unused output channel in debug builds.
+pub const OUTPUT_POISON: u8 = 0xcd;
 
+/// Returns the input channel, rewound to its start.
+///
+/// The reader is bounded to the length the host passed to `setInputLength`
+/// for this call, if any. The length holds for every call to this function
+/// until the export returns through [`error::handle`].
 #[allow(static_mut_refs)]
 pub fn get_input() -> &'static mut Reader<'static> {
     unsafe {
@@ -17,10 +40,20 @@ pub fn get_input() -> &'static mut Reader<'static> {
 
         reader.reset();
 
+        if let Some(length) = INPUT_LENGTH {
+            reader
+                .set_payload_length(length)
+                .expect("Input length exceeds channel");
+        }
+
         reader
     }
 }
 
+/// Returns the output channel, rewound to its start.
+///
+/// In debug builds [`error::handle`] fills the channel with [`OUTPUT_POISON`]
+/// once as each call starts, so bytes the call never writes stand out.
 #[allow(static_mut_refs)]
 pub fn get_output() -> &'static mut Writer<'static> {
     unsafe {
@@ -32,42 +65,283 @@ pub fn get_output() -> &'static mut Writer<'static> {
     }
 }
 
-fn allocate_buffer(size_in_bytes: i32) -> (*mut u64, usize) {
-    use std::alloc::{alloc, Layout};
+/// Records how many bytes the host wrote to the input channel for the next call.
+///
+/// Every [`get_input`] during that call bounds the reader to this length, so
+/// reading past the payload traps instead of returning stale data from a
+/// previous call. [`error::handle`] drops the length when the call returns,
+/// so a later call that skips `setInputLength` reads the whole channel again.
+///
+/// Unlike exports wrapped in [`error::handle`], this keeps the output and
+/// logs of the previous call, so the host can still read them afterwards.
+///
+/// # Returns
+///
+/// [`OK`], or [`error::ERROR`] with a message in the error region if the input
+/// channel isn't allocated, or the length is negative or exceeds the channel.
+#[allow(static_mut_refs)]
+pub fn set_input_length(length: i32) -> i32 {
+    error::status_or_error(unsafe { try_set_input_length(length) })
+}
+
+#[allow(static_mut_refs)]
+unsafe fn try_set_input_length(length: i32) -> error::Result<()> {
+    let reader = INPUT.as_mut().ok_or_else(|| {
+        Error::stored(format_args!("Input channel not initialized"))
+            .with_code(ErrorCode::INVALID_INPUT)
+    })?;
 
-    let size_in_u64s = (size_in_bytes as usize) / 8;
-    // Use 16-byte alignment for optimal SIMD performance
-    let layout = Layout::from_size_align(size_in_bytes as usize, 16).unwrap();
+    reader.reset();
+    let length = u32::try_from(length).map_err(|_| {
+        Error::stored(format_args!("Invalid input length {}", length))
+            .with_code(ErrorCode::INVALID_INPUT)
+    })?;
+    reader.set_payload_length(length)?;
 
+    INPUT_LENGTH = Some(length);
+
+    Ok(())
+}
+
+/// Returns the number of bytes written to the output channel.
+///
+/// [`error::handle`] rewinds the output channel as each call starts, so this
+/// is `0` for a call that failed or never wrote its output. Exports that don't
+/// use `handle` must fetch the output channel with [`get_output`] on every
+/// call for this to stay accurate.
+#[allow(static_mut_refs)]
+pub fn get_output_length() -> i32 {
+    unsafe { OUTPUT.as_ref().map_or(0, |writer| writer.length() as i32) }
+}
+
+/// Rewinds both channels, discarding any open sections, after a call was abandoned.
+#[allow(static_mut_refs)]
+#[cfg_attr(not(feature = "catch-unwind"), allow(dead_code))]
+pub(crate) fn reset_channels() {
     unsafe {
-        let ptr = alloc(layout) as *mut u64;
-        if ptr.is_null() {
-            panic!("Failed to allocate channel storage");
+        if let Some(reader) = INPUT.as_mut() {
+            reader.reset();
         }
+    }
 
-        // Touch the memory to force WASM runtime to allocate pages
-        std::ptr::write_bytes(ptr, 0, size_in_u64s);
+    end_call();
+    reset_output();
+}
 
-        (ptr, size_in_u64s)
+/// Rewinds the output channel, so its length is `0` until the call writes to it.
+///
+/// Debug builds also poison the whole channel with [`OUTPUT_POISON`].
+#[allow(static_mut_refs)]
+pub(crate) fn reset_output() {
+    unsafe {
+        if let Some(writer) = OUTPUT.as_mut() {
+            writer.reset();
+
+            #[cfg(debug_assertions)]
+            writer.fill_remaining(OUTPUT_POISON);
+        }
     }
 }
 
-pub fn allocate_input_channel(size_in_bytes: i32) -> i32 {
-    let (ptr, size_in_u64s) = allocate_buffer(size_in_bytes);
-
+/// Drops the input length the host set for the call that just returned.
+#[allow(static_mut_refs)]
+pub(crate) fn end_call() {
     unsafe {
-        let slice = std::slice::from_raw_parts_mut(ptr, size_in_u64s);
-        INPUT = Some(Reader::from(slice));
-        ptr as i32
+        INPUT_LENGTH = None;
     }
 }
 
-pub fn allocate_output_channel(size_in_bytes: i32) -> i32 {
-    let (ptr, size_in_u64s) = allocate_buffer(size_in_bytes);
+/// Returns the input channel after validating its message header.
+///
+/// Use this instead of [`get_input`] when the host writes a message header
+/// (see [`Writer::begin_message`]). The returned reader is positioned at the
+/// start of the payload and bounded to the payload length.
+///
+/// # Errors
+///
+/// Returns an error describing the mismatch if the magic bytes, protocol
+/// version or schema id don't match, or the payload length exceeds the channel.
+pub fn get_input_with_header(schema_id: u32) -> error::Result<&'static mut Reader<'static>> {
+    let reader = get_input();
+
+    reader.read_message_header(schema_id)?;
+
+    Ok(reader)
+}
+
+/// Returns the output channel with a message header already written.
+///
+/// Call [`Writer::end_message`] once the payload is complete so the header
+/// records the payload length.
+pub fn get_output_with_header(schema_id: u32) -> &'static mut Writer<'static> {
+    let writer = get_output();
+
+    writer.begin_message(schema_id);
+
+    writer
+}
+
+/// The alignment of channel storage, chosen for optimal SIMD performance.
+const CHANNEL_ALIGN: usize = 16;
+
+/// Returns the layout for a channel, rounding the size up to the channel alignment.
+fn channel_layout(size_in_bytes: i32) -> error::Result<Layout> {
+    let invalid = || {
+        Error::stored(format_args!("Invalid channel size {}", size_in_bytes))
+            .with_code(ErrorCode::INVALID_INPUT)
+    };
+
+    if size_in_bytes <= 0 {
+        return Err(invalid());
+    }
+
+    let size = (size_in_bytes as usize)
+        .checked_next_multiple_of(CHANNEL_ALIGN)
+        .ok_or_else(invalid)?;
+
+    Layout::from_size_align(size, CHANNEL_ALIGN).map_err(|_| invalid())
+}
 
+/// Converts a channel pointer to an export return value, clearing the error
+/// region, or writing any error to it and returning `0`, which is never a valid pointer.
+fn ptr_or_error(result: error::Result<*mut u64>) -> i32 {
+    match result {
+        Ok(ptr) => {
+            error::clear_error();
+            ptr as i32
+        }
+        Err(err) => {
+            err.store();
+            0
+        }
+    }
+}
+
+/// Statically reserved, 16-byte aligned storage for a channel of `WORDS` u64 words.
+///
+/// Declared by `setup_interop!(input = .., output = ..)` so that channels live
+/// in the data segment rather than on the heap.
+#[repr(C, align(16))]
+pub struct StaticChannel<const WORDS: usize>(UnsafeCell<[u64; WORDS]>);
+
+// Only the interop layer touches the storage, and WASM modules are single threaded
+unsafe impl<const WORDS: usize> Sync for StaticChannel<WORDS> {}
+
+impl<const WORDS: usize> StaticChannel<WORDS> {
+    pub const fn new() -> Self {
+        Self(UnsafeCell::new([0; WORDS]))
+    }
+}
+
+impl<const WORDS: usize> Default for StaticChannel<WORDS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the number of u64 words needed for a static channel of `size_in_bytes`,
+/// rounded up to the channel alignment.
+pub const fn static_channel_words(size_in_bytes: usize) -> usize {
+    size_in_bytes.div_ceil(CHANNEL_ALIGN) * (CHANNEL_ALIGN / 8)
+}
+
+/// Validates a requested size against static storage, returning its pointer and length in words.
+fn static_slice<const WORDS: usize>(
+    storage: &'static StaticChannel<WORDS>,
+    size_in_bytes: i32,
+) -> error::Result<(*mut u64, usize)> {
+    let layout = channel_layout(size_in_bytes)?;
+    let capacity = WORDS * 8;
+
+    if layout.size() > capacity {
+        return Err(Error::stored(format_args!(
+            "Channel size {} exceeds static capacity {}",
+            size_in_bytes, capacity
+        ))
+        .with_code(ErrorCode::CAPACITY_EXCEEDED));
+    }
+
+    Ok((storage.0.get() as *mut u64, layout.size() / 8))
+}
+
+/// Binds the input channel to statically reserved storage.
+///
+/// Any existing contents are kept, so this also serves as reallocation.
+///
+/// # Returns
+///
+/// A pointer to the channel storage, or `0` with a message in the error region
+/// if the size is invalid or exceeds the reserved capacity.
+#[allow(static_mut_refs)]
+pub fn bind_static_input_channel<const WORDS: usize>(
+    storage: &'static StaticChannel<WORDS>,
+    size_in_bytes: i32,
+) -> i32 {
+    ptr_or_error(
+        static_slice(storage, size_in_bytes).map(|(ptr, len)| unsafe {
+            INPUT = None;
+            #[cfg(feature = "alloc")]
+            heap::free_input_buffer();
+
+            // The interop layer holds the only reference, replacing it on each bind
+            let slice = core::slice::from_raw_parts_mut(ptr, len);
+            INPUT = Some(Reader::from(slice));
+            INPUT_LENGTH = None;
+
+            ptr
+        }),
+    )
+}
+
+/// Binds the output channel to statically reserved storage.
+///
+/// Any existing contents are kept, so this also serves as reallocation.
+///
+/// # Returns
+///
+/// A pointer to the channel storage, or `0` with a message in the error region
+/// if the size is invalid or exceeds the reserved capacity.
+#[allow(static_mut_refs)]
+pub fn bind_static_output_channel<const WORDS: usize>(
+    storage: &'static StaticChannel<WORDS>,
+    size_in_bytes: i32,
+) -> i32 {
+    ptr_or_error(
+        static_slice(storage, size_in_bytes).map(|(ptr, len)| unsafe {
+            OUTPUT = None;
+            #[cfg(feature = "alloc")]
+            heap::free_output_buffer();
+
+            // The interop layer holds the only reference, replacing it on each bind
+            let slice = core::slice::from_raw_parts_mut(ptr, len);
+            OUTPUT = Some(Writer::from(slice));
+
+            ptr
+        }),
+    )
+}
+
+/// Frees both channels. They must be allocated again before the next call.
+///
+/// Statically reserved channels are unbound but their storage stays in place.
+#[allow(static_mut_refs)]
+pub fn free_channels() -> i32 {
     unsafe {
-        let slice = std::slice::from_raw_parts_mut(ptr, size_in_u64s);
-        OUTPUT = Some(Writer::from(slice));
-        ptr as i32
+        INPUT = None;
+        OUTPUT = None;
+        INPUT_LENGTH = None;
+
+        #[cfg(feature = "alloc")]
+        {
+            heap::free_input_buffer();
+            heap::free_output_buffer();
+        }
     }
+
+    error::clear_error();
+
+    OK
 }
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,169 @@
+use core::fmt::Display;
+use core::panic::Location;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use super::error::{set_error_code, write_error_fmt, ErrorCode};
+use super::stack::frames;
+
+/// Set when the error region already holds the message for the coming panic.
+static MESSAGE_STORED: AtomicBool = AtomicBool::new(false);
+
+/// Marks the error region as already holding the message for the coming panic,
+/// so the panic hook doesn't overwrite it. Used by [`zaw_panic!`](crate::zaw_panic).
+#[doc(hidden)]
+pub fn mark_message_stored() {
+    MESSAGE_STORED.store(true, Ordering::Relaxed);
+}
+
+// Native builds may panic on several threads at once, so each keeps its own
+#[cfg(feature = "std")]
+std::thread_local! {
+    static PANIC_CODE: core::cell::Cell<ErrorCode> = const { core::cell::Cell::new(ErrorCode::PANIC) };
+}
+
+#[cfg(not(feature = "std"))]
+static mut PANIC_CODE: ErrorCode = ErrorCode::PANIC;
+
+/// Sets the code the coming panic reports through `getErrorCode`, in place of
+/// [`ErrorCode::PANIC`], for panics caused by the host's input rather than a bug.
+pub fn set_panic_code(code: ErrorCode) {
+    #[cfg(feature = "std")]
+    PANIC_CODE.set(code);
+
+    #[cfg(not(feature = "std"))]
+    unsafe {
+        PANIC_CODE = code
+    }
+}
+
+#[cfg_attr(not(any(feature = "std", feature = "panic-handler")), allow(dead_code))]
+fn take_panic_code() -> ErrorCode {
+    #[cfg(feature = "std")]
+    return PANIC_CODE.replace(ErrorCode::PANIC);
+
+    #[cfg(not(feature = "std"))]
+    unsafe {
+        let code = PANIC_CODE;
+        PANIC_CODE = ErrorCode::PANIC;
+        code
+    }
+}
+
+/// Writes a panic message, its location and any `trace!` frames into the error region.
+#[cfg_attr(not(any(feature = "std", feature = "panic-handler")), allow(dead_code))]
+pub(super) fn write_panic(message: impl Display, location: Option<&Location>) {
+    set_error_code(take_panic_code());
+
+    // Records buffered before the panic are the most useful for working out why
+    super::log::flush();
+
+    if MESSAGE_STORED.swap(false, Ordering::Relaxed) {
+        return;
+    }
+
+    match location {
+        Some(location) => write_error_fmt(format_args!(
+            "{}\n    at {}:{}{}",
+            message,
+            location.file(),
+            location.line(),
+            frames()
+        )),
+        None => write_error_fmt(format_args!("{}{}", message, frames())),
+    }
+}
+
+/// Writes the panic `info` describes into the error region, as the panic hook does.
+#[cfg(feature = "std")]
+pub(super) fn write_panic_info(info: &std::panic::PanicHookInfo) {
+    use std::string::String;
+
+    let payload = info.payload();
+    let message = payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("Box<dyn Any>");
+
+    write_panic(message, info.location());
+}
+
+/// Installs a panic hook that writes every panic into the error region before
+/// the module traps, so the host can report it. Safe to call repeatedly.
+///
+/// The previous hook still runs afterwards. `setup_interop!` calls this from
+/// every generated export, so it's in place before the host's first call.
+///
+/// Without `std` this does nothing; enable the `panic-handler` feature instead.
+#[cfg(all(feature = "std", not(test)))]
+pub fn install_panic_hook() {
+    use std::boxed::Box;
+    use std::sync::Once;
+
+    static INSTALLED: Once = Once::new();
+
+    INSTALLED.call_once(|| {
+        let previous = std::panic::take_hook();
+
+        std::panic::set_hook(Box::new(move |info| {
+            write_panic_info(info);
+
+            previous(info);
+        }));
+    });
+}
+
+// Tests run in parallel and many panic on purpose, so they install a hook that
+// only writes the region for the test holding it
+#[cfg(all(feature = "std", test))]
+pub(crate) use super::test::install_panic_hook;
+
+#[cfg(not(feature = "std"))]
+pub fn install_panic_hook() {}
+
+/// Runs `func`, returning `on_panic` instead of unwinding if it panics.
+///
+/// The panic hook has already written the message and [`ErrorCode::PANIC`]
+/// by the time this returns, and both channels are reset so the next call
+/// starts from a clean state.
+///
+/// Only with the `catch-unwind` feature, and only when the module is built
+/// with `panic = "unwind"`; otherwise panics trap as usual.
+#[cfg(feature = "catch-unwind")]
+pub fn catch_panic<F>(on_panic: i32, func: F) -> i32
+where
+    F: FnOnce() -> i32,
+{
+    install_panic_hook();
+
+    // Nothing observes state from the failed call except the channels, which are reset
+    match std::panic::catch_unwind(std::panic::AssertUnwindSafe(func)) {
+        Ok(result) => result,
+        Err(_) => {
+            super::reset_channels();
+            on_panic
+        }
+    }
+}
+
+#[cfg(not(feature = "catch-unwind"))]
+#[inline(always)]
+pub fn catch_panic<F>(_on_panic: i32, func: F) -> i32
+where
+    F: FnOnce() -> i32,
+{
+    func()
+}
+
+/// Writes the panic into the error region, then traps.
+#[cfg(all(feature = "panic-handler", not(feature = "std"), not(test)))]
+#[panic_handler]
+fn panic_handler(info: &core::panic::PanicInfo) -> ! {
+    write_panic(info.message(), info.location());
+
+    #[cfg(target_arch = "wasm32")]
+    core::arch::wasm32::unreachable();
+
+    #[cfg(not(target_arch = "wasm32"))]
+    loop {}
+}
@@ -0,0 +1,172 @@
+use core::cell::UnsafeCell;
+use core::fmt;
+
+/// Appended to text that didn't fit in its region.
+pub const TRUNCATION_MARKER: &str = "...";
+
+/// The smallest size for an error or log region, in bytes.
+pub const MIN_REGION_SIZE: usize = 16;
+
+/// Statically reserved storage for the error or log region.
+///
+/// Declared by `setup_interop!(error = .., log = ..)` to size the regions.
+pub struct StaticRegion<const SIZE: usize>(UnsafeCell<[u8; SIZE]>);
+
+// Shared on the same terms as `StaticChannel`
+unsafe impl<const SIZE: usize> Sync for StaticRegion<SIZE> {}
+
+impl<const SIZE: usize> StaticRegion<SIZE> {
+    pub const fn new() -> Self {
+        assert!(SIZE >= MIN_REGION_SIZE, "Regions must be at least 16 bytes");
+
+        Self(UnsafeCell::new([0; SIZE]))
+    }
+
+    pub(super) fn as_raw(&'static self) -> (*mut u8, usize) {
+        (self.0.get() as *mut u8, SIZE)
+    }
+}
+
+impl<const SIZE: usize> Default for StaticRegion<SIZE> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Writes text into a fixed, null-terminated region of memory.
+///
+/// Text that doesn't fit is cut at a character boundary, so the region always
+/// holds valid UTF-8, and ends with [`TRUNCATION_MARKER`]. Writes never fail,
+/// so formatting always runs to completion.
+pub struct RegionWriter<'a> {
+    region: &'a mut [u8],
+    len: usize,
+    truncated: bool,
+}
+
+impl<'a> RegionWriter<'a> {
+    /// Creates a writer over `region`, clearing any existing text.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `region` is empty, since there'd be no room for the terminator.
+    pub fn new(region: &'a mut [u8]) -> Self {
+        region[0] = 0;
+
+        Self {
+            region,
+            len: 0,
+            truncated: false,
+        }
+    }
+
+    /// Creates a writer that appends to the text already in `region`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `region` is empty, since there'd be no room for the terminator.
+    pub fn append(region: &'a mut [u8]) -> Self {
+        let end = region.iter().position(|&b| b == 0);
+        let len = end.unwrap_or(region.len() - 1);
+        region[len] = 0;
+
+        Self {
+            region,
+            len,
+            truncated: false,
+        }
+    }
+
+    /// Returns the text written so far.
+    pub fn as_str(&self) -> &str {
+        // Only whole characters are ever copied in
+        unsafe { core::str::from_utf8_unchecked(&self.region[..self.len]) }
+    }
+
+    /// Returns `true` if any text was dropped because the region was full.
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Cuts the text back to at most `len` bytes, at a character boundary.
+    fn cut_to(&mut self, len: usize) {
+        let text = self.as_str();
+        let mut len = len.min(text.len());
+        while !text.is_char_boundary(len) {
+            len -= 1;
+        }
+        self.len = len;
+    }
+
+    fn push(&mut self, s: &str) {
+        self.region[self.len..self.len + s.len()].copy_from_slice(s.as_bytes());
+        self.len += s.len();
+    }
+}
+
+impl<'a> fmt::Write for RegionWriter<'a> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if self.truncated {
+            return Ok(());
+        }
+
+        // Leave space for the null terminator
+        let capacity = self.region.len() - 1;
+
+        if self.len + s.len() <= capacity {
+            self.push(s);
+        } else {
+            // Keep as much as fits alongside the marker, which may mean cutting earlier text
+            let keep = capacity.saturating_sub(TRUNCATION_MARKER.len());
+            if self.len > keep {
+                self.cut_to(keep);
+            } else {
+                let mut count = keep - self.len;
+                while !s.is_char_boundary(count) {
+                    count -= 1;
+                }
+                self.push(&s[..count]);
+            }
+
+            if self.len + TRUNCATION_MARKER.len() <= capacity {
+                self.push(TRUNCATION_MARKER);
+            }
+            self.truncated = true;
+        }
+
+        self.region[self.len] = 0;
+
+        Ok(())
+    }
+}
+
+/// Returns the null-terminated text at the start of `region`.
+pub(super) fn read_region(region: &[u8]) -> &str {
+    let len = region.iter().position(|&b| b == 0).unwrap_or(region.len());
+    core::str::from_utf8(&region[..len]).unwrap_or("<invalid utf8>")
+}
+
+/// Writes null-terminated `key=value` fields after the message in `region`,
+/// ending with an empty field. Fields that don't fit are dropped whole.
+pub(super) fn write_fields(region: &mut [u8], fields: &[u8]) {
+    let message_len = region.iter().position(|&b| b == 0).unwrap_or(region.len());
+    let mut pos = message_len + 1;
+
+    for field in fields.split(|&b| b == 0).filter(|field| !field.is_empty()) {
+        // Leave room for this field's terminator and the final empty field
+        if pos + field.len() + 2 > region.len() {
+            break;
+        }
+
+        region[pos..pos + field.len()].copy_from_slice(field);
+        region[pos + field.len()] = 0;
+        pos += field.len() + 1;
+    }
+
+    if pos < region.len() {
+        region[pos] = 0;
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,102 @@
+use super::{read_region, write_fields, RegionWriter};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::fmt::Write;
+
+    #[test]
+    fn formats_into_region() {
+        let mut region = [0xffu8; 32];
+        let mut writer = RegionWriter::new(&mut region);
+
+        let place = "here";
+        write!(writer, "value {} at {}", 42, place).unwrap();
+
+        assert_eq!("value 42 at here", writer.as_str());
+        assert!(!writer.is_truncated());
+        assert_eq!("value 42 at here", read_region(&region));
+        assert_eq!(0, region[16]);
+    }
+
+    #[test]
+    fn truncates_at_region_boundary() {
+        let mut region = [0u8; 8];
+        let mut writer = RegionWriter::new(&mut region);
+
+        let (first, second) = ("abcd", "efghij");
+        write!(writer, "{}{}", first, second).unwrap();
+
+        assert_eq!("abcd...", writer.as_str());
+        assert!(writer.is_truncated());
+        assert_eq!(0, region[7]);
+    }
+
+    #[test]
+    fn truncates_at_char_boundary() {
+        let mut region = [0u8; 10];
+        let mut writer = RegionWriter::new(&mut region);
+
+        // Each snowman is three bytes, so only one fits before the marker
+        writer.write_str("a☃☃☃").unwrap();
+
+        assert_eq!("a☃...", writer.as_str());
+        assert!(writer.is_truncated());
+        assert_eq!("a☃...", read_region(&region));
+    }
+
+    #[test]
+    fn new_clears_existing_text() {
+        let mut region = *b"stale\0\0\0";
+        let writer = RegionWriter::new(&mut region);
+
+        assert_eq!("", writer.as_str());
+        assert_eq!("", read_region(&region));
+    }
+
+    #[test]
+    fn truncation_cuts_earlier_text_for_marker() {
+        let mut region = [0u8; 8];
+        let mut writer = RegionWriter::new(&mut region);
+
+        writer.write_str("ab☃").unwrap();
+        writer.write_str("cde").unwrap();
+
+        // Only four bytes fit alongside the marker, so the snowman is cut too
+        assert_eq!("ab...", writer.as_str());
+        writer.write_str("ignored").unwrap();
+        assert_eq!("ab...", read_region(&region));
+    }
+
+    #[test]
+    fn append_continues_existing_text() {
+        let mut region = *b"first\0\0\0\0\0\0\0";
+        let mut writer = RegionWriter::append(&mut region);
+
+        writer.write_str(" second").unwrap();
+
+        assert_eq!("first se...", writer.as_str());
+        assert_eq!("first se...", read_region(&region));
+    }
+
+    #[test]
+    fn writes_fields_after_message() {
+        let mut region = [0xffu8; 24];
+        RegionWriter::new(&mut region).write_str("bad").unwrap();
+
+        write_fields(&mut region, b"a=1\0b=22\0");
+
+        assert_eq!(b"bad\0a=1\0b=22\0\0", &region[..14]);
+        assert_eq!("bad", read_region(&region));
+    }
+
+    #[test]
+    fn drops_fields_that_dont_fit() {
+        let mut region = [0xffu8; 12];
+        RegionWriter::new(&mut region).write_str("bad").unwrap();
+
+        write_fields(&mut region, b"a=1\0long=value\0");
+
+        assert_eq!(b"bad\0a=1\0\0", &region[..9]);
+    }
+}
@@ -0,0 +1,194 @@
+use core::fmt;
+use core::panic::Location;
+
+/// The number of source locations the trace stack holds.
+///
+/// Deeper frames are still counted, so pops stay balanced, but only the
+/// outermost frames are reported.
+pub const MAX_TRACE_DEPTH: usize = 20;
+
+/// A fixed-size stack of source locations, innermost last.
+#[cfg(feature = "trace")]
+pub struct Stack<const N: usize> {
+    frames: [Option<&'static Location<'static>>; N],
+    depth: usize,
+}
+
+#[cfg(feature = "trace")]
+impl<const N: usize> Stack<N> {
+    pub const fn new() -> Self {
+        Self {
+            frames: [None; N],
+            depth: 0,
+        }
+    }
+
+    /// Pushes a location, which is dropped if the stack is full.
+    pub fn push(&mut self, location: &'static Location<'static>) {
+        if self.depth < N {
+            self.frames[self.depth] = Some(location);
+        }
+
+        self.depth += 1;
+    }
+
+    pub fn pop(&mut self) {
+        self.depth = self.depth.saturating_sub(1);
+    }
+
+    /// Clears the stack and pushes the entry point of a call from the host.
+    ///
+    /// Guards aren't dropped when a module traps, so this discards any frames
+    /// left over from a previous call.
+    pub fn entry(&mut self, location: &'static Location<'static>) {
+        self.depth = 0;
+        self.push(location);
+    }
+
+    /// Returns the number of frames pushed, including any that didn't fit.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the recorded frames, innermost first.
+    pub fn frames(&self) -> impl Iterator<Item = &'static Location<'static>> + '_ {
+        self.frames[..self.depth.min(N)]
+            .iter()
+            .rev()
+            .flatten()
+            .copied()
+    }
+}
+
+#[cfg(feature = "trace")]
+impl<const N: usize> Default for Stack<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "trace")]
+static mut STACK: Stack<MAX_TRACE_DEPTH> = Stack::new();
+
+/// Pushes a location onto the trace stack. Prefer [`trace!`](crate::trace).
+#[allow(static_mut_refs)]
+pub fn push(location: &'static Location<'static>) {
+    #[cfg(feature = "trace")]
+    unsafe {
+        STACK.push(location)
+    }
+
+    #[cfg(not(feature = "trace"))]
+    let _ = location;
+}
+
+/// Pops the innermost location from the trace stack.
+#[allow(static_mut_refs)]
+pub fn pop() {
+    #[cfg(feature = "trace")]
+    unsafe {
+        STACK.pop()
+    }
+}
+
+/// Resets the trace stack to a single entry point location.
+#[allow(static_mut_refs)]
+pub fn entry(location: &'static Location<'static>) {
+    #[cfg(feature = "trace")]
+    unsafe {
+        STACK.entry(location)
+    }
+
+    #[cfg(not(feature = "trace"))]
+    let _ = location;
+}
+
+/// Formats the trace stack as `at file:line` frames, innermost first.
+///
+/// Each frame starts on a new line, so this can follow a message directly.
+/// Without the `trace` feature it formats nothing.
+pub struct Frames;
+
+pub fn frames() -> Frames {
+    Frames
+}
+
+impl fmt::Display for Frames {
+    #[allow(static_mut_refs)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        #[cfg(feature = "trace")]
+        for location in unsafe { STACK.frames() } {
+            write!(f, "\n    at {}:{}", location.file(), location.line())?;
+        }
+
+        #[cfg(not(feature = "trace"))]
+        let _ = f;
+
+        Ok(())
+    }
+}
+
+/// Pops its location from the trace stack when dropped. Created by [`trace!`](crate::trace).
+#[must_use = "the location is popped as soon as the guard is dropped"]
+pub struct TraceGuard {
+    _private: (),
+}
+
+impl TraceGuard {
+    /// Pushes the caller's location for the rest of its scope.
+    #[track_caller]
+    pub fn push() -> Self {
+        push(Location::caller());
+
+        Self { _private: () }
+    }
+
+    /// Resets the stack to the caller's location for the rest of its scope.
+    #[track_caller]
+    pub fn entry() -> Self {
+        entry(Location::caller());
+
+        Self { _private: () }
+    }
+}
+
+impl Drop for TraceGuard {
+    fn drop(&mut self) {
+        pop();
+    }
+}
+
+/// Records the current source location on the trace stack until the end of
+/// the enclosing scope.
+///
+/// `zaw_error!`, `zaw_panic!` and the panic hook append the stack to their
+/// message as `at file:line` frames. Use `trace!(entry)` at the top of each
+/// exported function to discard frames left by a call that trapped.
+///
+/// Compiles to nothing without the `trace` feature.
+///
+/// ```rust
+/// fn parse() {
+///     zaw::trace!();
+///     // ..
+/// }
+///
+/// #[no_mangle]
+/// pub extern "C" fn process() -> i32 {
+///     zaw::trace!(entry);
+///     parse();
+///     zaw::interop::OK
+/// }
+/// ```
+#[macro_export]
+macro_rules! trace {
+    () => {
+        let _zaw_trace = $crate::interop::stack::TraceGuard::push();
+    };
+    (entry) => {
+        let _zaw_trace = $crate::interop::stack::TraceGuard::entry();
+    };
+}
+
+#[cfg(all(test, feature = "trace"))]
+mod test;
@@ -0,0 +1,60 @@
+use super::Stack;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::panic::Location;
+
+    #[track_caller]
+    fn here() -> &'static Location<'static> {
+        Location::caller()
+    }
+
+    #[test]
+    fn lists_frames_innermost_first() {
+        let mut stack = Stack::<4>::new();
+        let (outer, inner) = (here(), here());
+
+        stack.push(outer);
+        stack.push(inner);
+
+        let frames: Vec<_> = stack.frames().collect();
+        assert_eq!(vec![inner, outer], frames);
+
+        stack.pop();
+        assert_eq!(1, stack.depth());
+        assert_eq!(Some(outer), stack.frames().next());
+    }
+
+    #[test]
+    fn keeps_outermost_frames_when_full() {
+        let mut stack = Stack::<2>::new();
+        let (first, second, third) = (here(), here(), here());
+
+        stack.push(first);
+        stack.push(second);
+        stack.push(third);
+
+        assert_eq!(3, stack.depth());
+        assert_eq!(2, stack.frames().count());
+        assert_eq!(Some(second), stack.frames().next());
+
+        // Pops stay balanced with pushes that didn't fit
+        stack.pop();
+        stack.pop();
+        assert_eq!(Some(first), stack.frames().next());
+    }
+
+    #[test]
+    fn entry_discards_previous_frames() {
+        let mut stack = Stack::<4>::new();
+        let (stale, start) = (here(), here());
+
+        stack.push(stale);
+        stack.push(stale);
+        stack.entry(start);
+
+        assert_eq!(1, stack.depth());
+        assert_eq!(Some(start), stack.frames().next());
+    }
+}
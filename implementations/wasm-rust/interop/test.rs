@@ -0,0 +1,955 @@
+use super::error::{self, handle, stored_message, ErrorCode, ERROR, OK};
+use super::externs::{HOST_BATCHES, HOST_LOGS};
+#[cfg_attr(
+    any(
+        feature = "max-level-off",
+        feature = "max-level-error",
+        feature = "max-level-warn"
+    ),
+    allow(unused_imports)
+)]
+use super::log::{self, Level, LevelFilter, LogMode};
+use super::panic;
+#[cfg(feature = "alloc")]
+use super::{
+    allocate_input_channel, allocate_output_channel, heap, reallocate_input_channel,
+    reallocate_output_channel,
+};
+use super::{bind_static_input_channel, bind_static_output_channel, free_channels};
+use super::{get_input, get_output, get_output_length, set_input_length};
+use super::{get_input_with_header, get_output_with_header, static_channel_words, StaticChannel};
+use crate::conduit::{Reader, Writer};
+use std::cell::Cell;
+
+std::thread_local! {
+    static CAPTURE_PANICS: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Stands in for the module's panic hook, writing only the panics of threads
+/// that opted in with [`CAPTURE_PANICS`].
+pub(crate) fn install_panic_hook() {
+    static INSTALLED: std::sync::Once = std::sync::Once::new();
+
+    INSTALLED.call_once(|| {
+        let previous = std::panic::take_hook();
+
+        std::panic::set_hook(Box::new(move |info| {
+            if CAPTURE_PANICS.get() {
+                super::panic::write_panic_info(info);
+            }
+
+            previous(info);
+        }));
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Mutex, MutexGuard};
+
+    // The regions, channels and error code are global, so tests touching them take turns
+    static REGIONS: Mutex<()> = Mutex::new(());
+
+    fn lock_regions() -> MutexGuard<'static, ()> {
+        // As in a module, where every generated export installs it
+        panic::install_panic_hook();
+        CAPTURE_PANICS.set(true);
+
+        REGIONS
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    #[test]
+    fn handle_returns_ok_or_error() {
+        let _regions = lock_regions();
+
+        assert_eq!(OK, handle(|| Ok(())));
+        assert_eq!(
+            ERROR,
+            handle(|| Err(crate::zaw_error!("failed").with_code(ErrorCode::INTERNAL)))
+        );
+        assert!(stored_message().starts_with("failed\n    at "));
+        assert_eq!(ErrorCode::INTERNAL.value(), error::get_error_code());
+    }
+
+    #[cfg(feature = "catch-unwind")]
+    #[test]
+    fn handle_catches_panics() {
+        let _regions = lock_regions();
+
+        let result = handle(|| panic!("exploded"));
+
+        assert_eq!(error::PANIC, result);
+        assert!(stored_message().starts_with("exploded\n    at "));
+        assert_eq!(ErrorCode::PANIC.value(), error::get_error_code());
+    }
+
+    fn check_lengths(a: &[f32], b: &[f32]) -> error::Result<usize> {
+        crate::zaw_ensure!(
+            a.len() == b.len(),
+            "Expected {} values, found {}",
+            a.len(),
+            b.len()
+        );
+        crate::zaw_ensure!(!a.is_empty());
+
+        Ok(a.len())
+    }
+
+    #[test]
+    fn ensure_names_the_condition() {
+        let _regions = lock_regions();
+
+        assert_eq!(2, check_lengths(&[1.0, 2.0], &[3.0, 4.0]).unwrap());
+
+        let err = check_lengths(&[1.0], &[]).unwrap_err();
+        assert!(err.message().starts_with(
+            "Expected 1 values, found 0\n    condition: `a.len() == b.len()`\n    at "
+        ));
+
+        let err = check_lengths(&[], &[]).unwrap_err();
+        assert!(err
+            .message()
+            .starts_with("Condition failed: `!a.is_empty()`\n    at "));
+    }
+
+    #[test]
+    fn bail_returns_early() {
+        let _regions = lock_regions();
+
+        fn bail() -> error::Result<()> {
+            crate::zaw_bail!("Gave up after {} tries", 3);
+        }
+
+        assert!(bail()
+            .unwrap_err()
+            .message()
+            .starts_with("Gave up after 3 tries\n    at "));
+    }
+
+    #[test]
+    #[should_panic(expected = "Assertion failed: `1 + 1 == 3`")]
+    fn assert_panics_with_condition() {
+        let _regions = lock_regions();
+
+        crate::zaw_assert!(1 + 1 == 3);
+    }
+
+    fn fail() -> error::Result<()> {
+        Err(crate::zaw_error!("failed")
+            .with_code(ErrorCode::INVALID_INPUT)
+            .with_field("count", 3))
+    }
+
+    #[test]
+    fn fields_that_dont_fit_are_dropped_whole() {
+        let _regions = lock_regions();
+
+        let long = "x".repeat(error::MAX_FIELD_BYTES);
+        let result = handle(|| {
+            Err(error::Error::from_static("failed")
+                .with_field("a", 1)
+                .with_field("long", &long)
+                .with_field("b", 2))
+        });
+
+        assert_eq!(ERROR, result);
+
+        let region = unsafe { core::slice::from_raw_parts(stored_message().as_ptr(), 16) };
+        assert_eq!(b"failed\0a=1\0b=2\0\0", region);
+    }
+
+    #[test]
+    fn payload_errors_carry_their_fields() {
+        let _regions = lock_regions();
+        bind_channels();
+
+        assert_eq!(ERROR, set_input_length(CHANNEL_SIZE + 16));
+
+        let message = stored_message();
+        assert!(message.starts_with(&format!(
+            "Payload length {} exceeds channel capacity {}\n    at ",
+            CHANNEL_SIZE + 16,
+            CHANNEL_SIZE
+        )));
+
+        let fields = format!(
+            "length={}\0capacity={}\0\0",
+            CHANNEL_SIZE + 16,
+            CHANNEL_SIZE
+        );
+        let region = unsafe {
+            core::slice::from_raw_parts(message.as_ptr().add(message.len() + 1), fields.len())
+        };
+        assert_eq!(fields.as_bytes(), region);
+    }
+
+    #[test]
+    fn stored_errors_share_the_region() {
+        let _regions = lock_regions();
+
+        let first = error::Error::stored(format_args!("first"));
+        let second = error::Error::stored(format_args!("second"));
+
+        assert_eq!("second", first.message());
+        assert_eq!("second", second.message());
+    }
+
+    #[test]
+    fn success_clears_previous_error() {
+        let _regions = lock_regions();
+
+        assert_eq!(ERROR, handle(fail));
+        assert!(!stored_message().is_empty());
+
+        assert_eq!(OK, handle(|| Ok(())));
+        assert_eq!("", stored_message());
+        assert_eq!(ErrorCode::UNSPECIFIED.value(), error::get_error_code());
+    }
+
+    #[test]
+    fn calls_start_with_a_clear_region() {
+        let _regions = lock_regions();
+
+        assert_eq!(ERROR, handle(fail));
+
+        // A call that traps part way through must not leave the last call's error behind
+        assert_eq!(
+            OK,
+            handle(|| {
+                assert_eq!("", stored_message());
+                Ok(())
+            })
+        );
+    }
+
+    #[test]
+    fn errors_replace_each_other() {
+        let _regions = lock_regions();
+
+        assert_eq!(ERROR, handle(fail));
+        assert_eq!(ERROR, handle(|| Err(error::Error::from_static("second"))));
+        assert_eq!("second", stored_message());
+        assert_eq!(ErrorCode::UNSPECIFIED.value(), error::get_error_code());
+    }
+
+    #[test]
+    fn free_channels_clears_error() {
+        let _regions = lock_regions();
+
+        assert_eq!(ERROR, handle(fail));
+        assert_eq!(OK, free_channels());
+        assert_eq!("", stored_message());
+    }
+
+    #[cfg(feature = "catch-unwind")]
+    #[test]
+    fn success_after_panic_clears_error() {
+        let _regions = lock_regions();
+
+        assert_eq!(error::PANIC, handle(|| panic!("exploded")));
+        assert_eq!(ErrorCode::PANIC.value(), error::get_error_code());
+
+        assert_eq!(OK, handle(|| Ok(())));
+        assert_eq!("", stored_message());
+        assert_eq!(ErrorCode::UNSPECIFIED.value(), error::get_error_code());
+    }
+
+    #[test]
+    fn panic_hook_writes_message_and_location() {
+        let _regions = lock_regions();
+
+        let line = line!() + 1;
+        let result = std::panic::catch_unwind(|| panic!("exploded {}", 3));
+
+        assert!(result.is_err());
+        assert_eq!(
+            format!("exploded 3\n    at {}:{}", file!(), line),
+            stored_message()
+        );
+        assert_eq!(ErrorCode::PANIC.value(), error::get_error_code());
+    }
+
+    #[test]
+    fn panic_hook_keeps_stored_message() {
+        let _regions = lock_regions();
+
+        let result = std::panic::catch_unwind(|| crate::zaw_panic!("exploded {}", 3));
+
+        // zaw_panic! already wrote the message, so the hook doesn't repeat it
+        assert!(result.is_err());
+        assert!(stored_message().starts_with("exploded 3\n    at "));
+        assert_eq!(1, stored_message().matches("\n    at ").count());
+        assert_eq!(ErrorCode::PANIC.value(), error::get_error_code());
+    }
+
+    #[test]
+    fn write_panic_formats_message_and_location() {
+        let _regions = lock_regions();
+
+        // As the no_std panic handler calls it
+        let location = core::panic::Location::caller();
+        panic::write_panic(format_args!("exploded {}", 3), Some(location));
+
+        assert_eq!(
+            format!("exploded 3\n    at {}:{}", location.file(), location.line()),
+            stored_message()
+        );
+        assert_eq!(ErrorCode::PANIC.value(), error::get_error_code());
+
+        panic::write_panic("exploded", None);
+        assert_eq!("exploded", stored_message());
+    }
+
+    #[cfg(feature = "catch-unwind")]
+    #[test]
+    fn catch_panic_returns_fallback_and_resets_channels() {
+        let _regions = lock_regions();
+        bind_channels();
+
+        assert_eq!(7, panic::catch_panic(0, || 7));
+
+        let result = panic::catch_panic(-1, || {
+            get_output().write_u32(1);
+            panic!("exploded")
+        });
+
+        assert_eq!(-1, result);
+        assert!(stored_message().starts_with("exploded\n    at "));
+        assert_eq!(0, get_output_length());
+    }
+
+    #[cfg(not(feature = "catch-unwind"))]
+    #[test]
+    fn catch_panic_passes_through_without_catch_unwind() {
+        assert_eq!(7, panic::catch_panic(0, || 7));
+        assert!(std::panic::catch_unwind(|| panic::catch_panic(0, || panic!("exploded"))).is_err());
+    }
+
+    const CHANNEL_SIZE: i32 = 256;
+    const CHANNEL_WORDS: usize = static_channel_words(CHANNEL_SIZE as usize);
+
+    static INPUT_STORAGE: StaticChannel<CHANNEL_WORDS> = StaticChannel::new();
+    static OUTPUT_STORAGE: StaticChannel<CHANNEL_WORDS> = StaticChannel::new();
+
+    #[allow(clippy::mut_from_ref)]
+    fn host_view(storage: &'static StaticChannel<CHANNEL_WORDS>) -> &'static mut [u64] {
+        // The host shares the channel memory with the module
+        unsafe { core::slice::from_raw_parts_mut(storage.0.get() as *mut u64, CHANNEL_WORDS) }
+    }
+
+    /// Binds both channels to static storage, returning the host's writer for the input.
+    fn bind_channels() -> Writer<'static> {
+        assert_ne!(0, bind_static_input_channel(&INPUT_STORAGE, CHANNEL_SIZE));
+        assert_ne!(0, bind_static_output_channel(&OUTPUT_STORAGE, CHANNEL_SIZE));
+
+        Writer::from(host_view(&INPUT_STORAGE))
+    }
+
+    #[test]
+    fn header_round_trips_through_channels() {
+        let _regions = lock_regions();
+        let mut host = bind_channels();
+
+        host.begin_message(7);
+        host.write_u32(42);
+        host.end_message();
+
+        let result = handle(|| {
+            let value = get_input_with_header(7)?.read_u32();
+
+            let output = get_output_with_header(7);
+            output.write_u32(value + 1);
+            output.end_message();
+
+            Ok(())
+        });
+
+        assert_eq!(OK, result);
+
+        let reader = Reader::from(host_view(&OUTPUT_STORAGE));
+        reader.read_message_header(7).unwrap();
+        assert_eq!(43, reader.read_u32());
+    }
+
+    #[test]
+    fn header_schema_mismatch_returns_error() {
+        let _regions = lock_regions();
+        let mut host = bind_channels();
+
+        host.begin_message(7);
+        host.end_message();
+
+        assert_eq!(ERROR, handle(|| get_input_with_header(8).map(|_| ())));
+        assert!(stored_message().starts_with("Schema mismatch: expected 8 but found 7\n    at "));
+    }
+
+    #[test]
+    fn missing_header_returns_error() {
+        let _regions = lock_regions();
+        bind_channels();
+
+        // A host that doesn't write a header
+        host_view(&INPUT_STORAGE)[0] = 42;
+
+        assert_eq!(ERROR, handle(|| get_input_with_header(7).map(|_| ())));
+        assert!(
+            stored_message().starts_with("Invalid message header: bad magic 0x0000002a\n    at ")
+        );
+    }
+
+    #[cfg(feature = "type-tags")]
+    #[test]
+    fn type_tag_mismatch_reports_invalid_input() {
+        let _regions = lock_regions();
+        let mut host = bind_channels();
+
+        // The host sends an f64 where the module reads an f32
+        host.write_f64(1.5);
+
+        let result = std::panic::catch_unwind(|| get_input().read_f32());
+
+        assert!(result.is_err());
+        assert!(stored_message().starts_with(
+            "Type tag mismatch at offset 0: expected Float32 value but found Float64 value\n    at "
+        ));
+        assert_eq!(ErrorCode::INVALID_INPUT.value(), error::get_error_code());
+    }
+
+    #[test]
+    fn input_length_applies_to_one_call() {
+        let _regions = lock_regions();
+        bind_channels();
+
+        assert_eq!(OK, set_input_length(8));
+        assert_eq!(
+            OK,
+            handle(|| {
+                assert_eq!(8, get_input().payload_length());
+                // A helper fetching the input again is still bounded
+                assert_eq!(8, get_input().payload_length());
+                Ok(())
+            })
+        );
+
+        // A call that skips setInputLength isn't bounded by the last one
+        assert_eq!(
+            OK,
+            handle(|| {
+                assert_eq!(CHANNEL_SIZE as u32, get_input().payload_length());
+                Ok(())
+            })
+        );
+    }
+
+    #[test]
+    fn output_length_only_counts_the_current_call() {
+        let _regions = lock_regions();
+        bind_channels();
+
+        let write = || {
+            get_output().write_f64(1.5);
+            Ok(())
+        };
+
+        assert_eq!(OK, handle(write));
+        assert_ne!(0, get_output_length());
+
+        // Neither a failed call nor one without output reports the last call's bytes
+        assert_eq!(ERROR, handle(fail));
+        assert_eq!(0, get_output_length());
+
+        assert_eq!(OK, handle(write));
+        assert_eq!(OK, handle(|| Ok(())));
+        assert_eq!(0, get_output_length());
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    fn calls_poison_unwritten_output() {
+        let _regions = lock_regions();
+        bind_channels();
+        host_view(&OUTPUT_STORAGE).fill(0);
+
+        let write = || {
+            get_output().write_u32(7);
+            Ok(())
+        };
+
+        assert_eq!(OK, handle(write));
+
+        let written = get_output_length() as usize;
+        let output = host_view(&OUTPUT_STORAGE);
+        let bytes = unsafe {
+            core::slice::from_raw_parts(output.as_ptr() as *const u8, CHANNEL_SIZE as usize)
+        };
+        assert!(bytes[written..]
+            .iter()
+            .all(|&byte| byte == crate::interop::OUTPUT_POISON));
+    }
+
+    #[test]
+    fn configuring_between_calls_keeps_the_output() {
+        let _regions = lock_regions();
+        bind_channels();
+
+        let write = || {
+            get_output().write_f64(1.5);
+            Ok(())
+        };
+
+        assert_eq!(OK, handle(write));
+        let length = get_output_length();
+        assert_ne!(0, length);
+
+        // The host may configure the next call before reading this one's output
+        assert_eq!(OK, log::set_log_level(log::DEFAULT_LOG_LEVEL as i32));
+        assert_eq!(OK, set_input_length(8));
+        assert_eq!(length, get_output_length());
+
+        assert_eq!(ERROR, set_input_length(-1));
+        assert_eq!("Invalid input length -1", stored_message());
+        assert_eq!(length, get_output_length());
+    }
+
+    #[test]
+    fn static_bind_uses_reserved_storage() {
+        let _regions = lock_regions();
+
+        let input = bind_static_input_channel(&INPUT_STORAGE, CHANNEL_SIZE);
+        let output = bind_static_output_channel(&OUTPUT_STORAGE, CHANNEL_SIZE);
+
+        assert_eq!(INPUT_STORAGE.0.get() as i32, input);
+        assert_eq!(OUTPUT_STORAGE.0.get() as i32, output);
+        assert_eq!(CHANNEL_SIZE as u32, get_input().payload_length());
+
+        get_output().write_u32(7);
+        assert_eq!(7, Reader::from(host_view(&OUTPUT_STORAGE)).read_u32());
+    }
+
+    #[test]
+    fn static_bind_rejects_invalid_sizes() {
+        let _regions = lock_regions();
+
+        assert_eq!(
+            0,
+            bind_static_input_channel(&INPUT_STORAGE, CHANNEL_SIZE + 16)
+        );
+        assert_eq!(
+            format!(
+                "Channel size {} exceeds static capacity {}",
+                CHANNEL_SIZE + 16,
+                CHANNEL_SIZE
+            ),
+            stored_message()
+        );
+        assert_eq!(
+            ErrorCode::CAPACITY_EXCEEDED.value(),
+            error::get_error_code()
+        );
+
+        assert_eq!(0, bind_static_output_channel(&OUTPUT_STORAGE, 0));
+        assert_eq!("Invalid channel size 0", stored_message());
+        assert_eq!(ErrorCode::INVALID_INPUT.value(), error::get_error_code());
+    }
+
+    #[test]
+    fn rebinding_keeps_contents() {
+        let _regions = lock_regions();
+        bind_channels();
+
+        host_view(&INPUT_STORAGE)[..2].copy_from_slice(&[1, 2]);
+        assert_eq!(OK, set_input_length(8));
+
+        // A smaller channel over the same storage, as a reallocation would give
+        assert_ne!(0, bind_static_input_channel(&INPUT_STORAGE, 64));
+        assert_eq!(&[1, 2], &host_view(&INPUT_STORAGE)[..2]);
+
+        // The input length described the old binding
+        assert_eq!(64, get_input().payload_length());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn static_bind_frees_heap_channels() {
+        let _regions = lock_regions();
+
+        assert_ne!(0, allocate_input_channel(16));
+        assert_ne!(0, allocate_output_channel(16));
+        bind_channels();
+
+        assert!(heap_channel(true).is_none());
+        assert!(heap_channel(false).is_none());
+    }
+
+    #[test]
+    fn free_channels_keeps_static_storage() {
+        let _regions = lock_regions();
+        bind_channels();
+
+        host_view(&INPUT_STORAGE)[..2].copy_from_slice(&[1, 2]);
+        host_view(&OUTPUT_STORAGE)[..2].copy_from_slice(&[3, 4]);
+
+        assert_eq!(OK, free_channels());
+
+        // Unbound, but the storage is still there to bind again
+        assert_eq!(0, get_output_length());
+        assert_eq!(&[1, 2], &host_view(&INPUT_STORAGE)[..2]);
+        assert_eq!(&[3, 4], &host_view(&OUTPUT_STORAGE)[..2]);
+
+        assert_ne!(0, bind_static_input_channel(&INPUT_STORAGE, CHANNEL_SIZE));
+        assert_eq!(&[1, 2], &host_view(&INPUT_STORAGE)[..2]);
+    }
+
+    /// Returns the contents of a heap allocated channel, as the host sees them.
+    #[cfg(feature = "alloc")]
+    #[allow(static_mut_refs)]
+    fn heap_channel(input: bool) -> Option<&'static mut [u64]> {
+        let buffer = unsafe {
+            if input {
+                heap::INPUT_BUFFER.as_ref()
+            } else {
+                heap::OUTPUT_BUFFER.as_ref()
+            }
+        }?;
+
+        Some(unsafe { core::slice::from_raw_parts_mut(buffer.ptr, buffer.layout.size() / 8) })
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn allocation_rejects_invalid_sizes() {
+        let _regions = lock_regions();
+
+        let exports: [fn(i32) -> i32; 4] = [
+            allocate_input_channel,
+            allocate_output_channel,
+            reallocate_input_channel,
+            reallocate_output_channel,
+        ];
+
+        for export in exports {
+            for size in [-16, 0] {
+                // 0 is never a valid pointer, and the error region says why
+                assert_eq!(0, export(size));
+                assert_eq!(format!("Invalid channel size {}", size), stored_message());
+                assert_eq!(ErrorCode::INVALID_INPUT.value(), error::get_error_code());
+            }
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn allocation_rounds_up_to_16_bytes() {
+        let _regions = lock_regions();
+
+        assert_eq!(0, allocate_input_channel(-1));
+        assert_ne!(0, allocate_input_channel(20));
+        assert_ne!(0, allocate_output_channel(1));
+
+        // Success clears the error left by the failed allocation
+        assert_eq!("", stored_message());
+        assert_eq!(4, heap_channel(true).unwrap().len());
+        assert_eq!(2, heap_channel(false).unwrap().len());
+        assert_eq!(32, get_input().payload_length());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn reallocation_keeps_contents() {
+        let _regions = lock_regions();
+
+        assert_ne!(0, allocate_input_channel(16));
+        heap_channel(true).unwrap().copy_from_slice(&[1, 2]);
+
+        assert_ne!(0, reallocate_input_channel(48));
+        assert_eq!(&[1, 2, 0, 0, 0, 0], heap_channel(true).unwrap());
+        assert_eq!(48, get_input().payload_length());
+
+        assert_ne!(0, reallocate_input_channel(8));
+        assert_eq!(&[1, 2], heap_channel(true).unwrap());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn failed_reallocation_leaves_channel_unchanged() {
+        let _regions = lock_regions();
+
+        assert_ne!(0, allocate_output_channel(16));
+        heap_channel(false).unwrap().copy_from_slice(&[3, 4]);
+        let before = heap_channel(false).unwrap().as_ptr();
+
+        assert_eq!(0, reallocate_output_channel(-1));
+        assert_eq!("Invalid channel size -1", stored_message());
+
+        let after = heap_channel(false).unwrap();
+        assert_eq!(before, after.as_ptr());
+        assert_eq!(&[3, 4], after);
+
+        // The channel is still bound and usable
+        get_output().write_u32(5);
+        assert_ne!(0, get_output_length());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn reallocation_allocates_a_missing_channel() {
+        let _regions = lock_regions();
+
+        assert_eq!(OK, free_channels());
+        assert_ne!(0, reallocate_input_channel(16));
+        assert_eq!(2, heap_channel(true).unwrap().len());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn free_channels_frees_heap_channels() {
+        let _regions = lock_regions();
+
+        assert_ne!(0, allocate_input_channel(16));
+        assert_ne!(0, allocate_output_channel(16));
+        assert_eq!(OK, free_channels());
+
+        assert!(heap_channel(true).is_none());
+        assert!(heap_channel(false).is_none());
+    }
+
+    fn take_host_logs() -> Vec<(i32, String)> {
+        HOST_LOGS.with(|logs| logs.take())
+    }
+
+    #[cfg(not(any(feature = "max-level-off", feature = "max-level-error")))]
+    #[test]
+    fn logs_only_enabled_levels() {
+        let _regions = lock_regions();
+        log::set_max_level(LevelFilter::Warn);
+
+        log::log("info");
+        log::log_at(Level::Warn, "warn");
+        crate::zaw_log!(Level::Error, "error {}", 1);
+        crate::zaw_log!(Level::Debug, "debug {}", 2);
+
+        log::set_max_level(log::DEFAULT_LOG_LEVEL);
+
+        assert_eq!(
+            vec![(2, "warn".to_string()), (1, "error 1".to_string())],
+            take_host_logs()
+        );
+    }
+
+    #[cfg(feature = "max-level-off")]
+    #[test]
+    fn max_level_off_logs_nothing() {
+        let _regions = lock_regions();
+        log::set_max_level(LevelFilter::Trace);
+
+        log::log_at(Level::Error, "dropped");
+        crate::zaw_log!(Level::Error, "dropped {}", 1);
+
+        log::set_max_level(log::DEFAULT_LOG_LEVEL);
+
+        assert!(!log::log_enabled(Level::Error));
+        assert!(take_host_logs().is_empty());
+    }
+
+    #[test]
+    fn host_sets_log_level() {
+        let _regions = lock_regions();
+
+        assert_eq!(OK, log::set_log_level(LevelFilter::Off as i32));
+        assert_eq!(LevelFilter::Off, log::log_level());
+        log::log_at(Level::Error, "dropped");
+        assert!(take_host_logs().is_empty());
+
+        assert_eq!(ERROR, log::set_log_level(6));
+        assert_eq!("Invalid log level 6", stored_message());
+        assert_eq!(LevelFilter::Off, log::log_level());
+
+        assert_eq!(OK, log::set_log_level(log::DEFAULT_LOG_LEVEL as i32));
+    }
+
+    #[cfg_attr(
+        any(
+            feature = "max-level-off",
+            feature = "max-level-error",
+            feature = "max-level-warn"
+        ),
+        allow(dead_code)
+    )]
+    fn take_host_batches() -> Vec<Vec<(i32, u32, String)>> {
+        HOST_BATCHES.with(|batches| batches.take())
+    }
+
+    #[cfg(not(any(
+        feature = "max-level-off",
+        feature = "max-level-error",
+        feature = "max-level-warn"
+    )))]
+    #[test]
+    fn buffered_logs_flush_once_per_call() {
+        let _regions = lock_regions();
+        log::set_log_mode(LogMode::Buffered);
+
+        let result = handle(|| {
+            log::log("first");
+            crate::zaw_log!(Level::Warn, "second {}", 2);
+            assert!(take_host_batches().is_empty());
+            Ok(())
+        });
+
+        log::set_log_mode(LogMode::Immediate);
+
+        assert_eq!(OK, result);
+        assert!(take_host_logs().is_empty());
+
+        let batches = take_host_batches();
+        assert_eq!(1, batches.len());
+
+        let [(3, first, ref a), (2, second, ref b)] = batches[0][..] else {
+            panic!("unexpected batch {:?}", batches[0]);
+        };
+        assert_eq!(("first", "second 2"), (a.as_str(), b.as_str()));
+        assert_eq!(first + 1, second);
+    }
+
+    #[cfg(not(any(
+        feature = "max-level-off",
+        feature = "max-level-error",
+        feature = "max-level-warn"
+    )))]
+    #[test]
+    fn buffered_logs_flush_on_overflow() {
+        let _regions = lock_regions();
+        log::set_log_mode(LogMode::Buffered);
+
+        let message = "x".repeat(log::DEFAULT_LOG_SIZE / 3);
+        for _ in 0..3 {
+            log::log(&message);
+        }
+
+        // The third record didn't fit, so the first two were sent to make room
+        let overflowed = take_host_batches();
+        log::flush();
+        let flushed = take_host_batches();
+        log::set_log_mode(LogMode::Immediate);
+
+        assert_eq!(vec![2], overflowed.iter().map(Vec::len).collect::<Vec<_>>());
+        assert_eq!(vec![1], flushed.iter().map(Vec::len).collect::<Vec<_>>());
+        assert_eq!(message, flushed[0][0].2);
+    }
+
+    // Joins chunks flagged as continued onto the chunk that follows, as hosts do
+    #[cfg_attr(
+        any(
+            feature = "max-level-off",
+            feature = "max-level-error",
+            feature = "max-level-warn"
+        ),
+        allow(dead_code)
+    )]
+    fn join_chunks(chunks: &[(i32, String)]) -> Vec<(i32, String)> {
+        let continued = log::LOG_CONTINUED as i32;
+        let mut messages = Vec::new();
+        let mut pending = String::new();
+
+        for (level, chunk) in chunks {
+            pending.push_str(chunk);
+            if level & continued == 0 {
+                messages.push((*level, std::mem::take(&mut pending)));
+            }
+        }
+
+        messages
+    }
+
+    #[cfg(not(any(feature = "max-level-off", feature = "max-level-error")))]
+    #[test]
+    fn long_logs_are_sent_in_chunks() {
+        let _regions = lock_regions();
+
+        // Multi-byte characters that don't line up with the region's size
+        let message = "é€".repeat(log::DEFAULT_LOG_SIZE / 2);
+        crate::zaw_log!(Level::Warn, "{}", message);
+
+        let chunks = take_host_logs();
+        let continued = (Level::Warn as u8 | log::LOG_CONTINUED) as i32;
+
+        assert_eq!(3, chunks.len());
+        assert!(chunks[..2].iter().all(|(level, _)| *level == continued));
+        assert_eq!(vec![(2, message)], join_chunks(&chunks));
+    }
+
+    #[cfg(not(any(
+        feature = "max-level-off",
+        feature = "max-level-error",
+        feature = "max-level-warn"
+    )))]
+    #[test]
+    fn long_logs_stop_at_max_length() {
+        let _regions = lock_regions();
+        log::set_max_log_length(12);
+
+        log::log("0123456789abcdef");
+        log::log("€€€€€");
+
+        log::set_max_log_length(log::DEFAULT_MAX_LOG_LENGTH);
+
+        assert_eq!(
+            vec![
+                (3, "0123456789ab...".to_string()),
+                (3, "€€€€...".to_string())
+            ],
+            take_host_logs()
+        );
+    }
+
+    #[cfg(not(any(
+        feature = "max-level-off",
+        feature = "max-level-error",
+        feature = "max-level-warn"
+    )))]
+    #[test]
+    fn buffered_long_logs_continue_in_the_next_batch() {
+        let _regions = lock_regions();
+        log::set_log_mode(LogMode::Buffered);
+
+        let message = "x".repeat(log::DEFAULT_LOG_SIZE * 2);
+        log::log("short");
+        log::log(&message);
+        log::flush();
+
+        log::set_log_mode(LogMode::Immediate);
+
+        let batches = take_host_batches();
+        let records: Vec<_> = batches
+            .iter()
+            .flatten()
+            .map(|(level, _, message)| (*level, message.clone()))
+            .collect();
+
+        // The short record went on its own, rather than sharing a batch with a chunk
+        assert_eq!(1, batches[0].len());
+        assert!(batches.iter().all(|batch| batch.len() == 1));
+        assert_eq!(
+            vec![(3, "short".to_string()), (3, message)],
+            join_chunks(&records)
+        );
+    }
+
+    #[cfg(all(
+        feature = "log",
+        not(any(feature = "max-level-off", feature = "max-level-error"))
+    ))]
+    #[test]
+    fn log_crate_records_reach_host() {
+        let _regions = lock_regions();
+        log::init_logger().unwrap();
+
+        ::log::warn!(target: "curve", "{} points", 3);
+        ::log::debug!("not enabled");
+
+        assert_eq!(vec![(2, "[curve] 3 points".to_string())], take_host_logs());
+    }
+}
@@ -1,39 +1,231 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 pub mod conduit;
 pub mod interop;
 
 /// Sets up all required WASM exports for the zaw interop layer.
 ///
-/// This macro generates the four required exports:
-/// - getErrorPtr: Access to error message buffer
-/// - getLogPtr: Access to log message buffer
-/// - allocateInputChannel: Allocate shared memory for JS→WASM communication
-/// - allocateOutputChannel: Allocate shared memory for WASM→JS communication
+/// Channel exports, backed by the heap unless `input` / `output` reserve them:
+/// - `allocateInputChannel` / `allocateOutputChannel`: Allocate shared memory
+///   for JS→WASM and WASM→JS communication
+/// - `reallocateInputChannel` / `reallocateOutputChannel`: Resize a channel
+/// - `freeChannels`: Release both channels
+/// - `setInputLength`: Optionally called by hosts before each function to
+///   report how many bytes they wrote to the input channel
+/// - `getOutputLength`: Report how many bytes the module wrote back
+///
+/// Region exports, sized by `error` / `log`:
+/// - `getErrorPtr` / `getErrorSize`: Locate the error message buffer
+/// - `getErrorCode`: Report the category of the last error
+/// - `getLogPtr` / `getLogSize`: Locate the log message buffer
+/// - `setLogLevel`: Choose how verbose the module's logging is
+///
+/// Every generated export also installs a panic hook, so that any panic, not
+/// just `zaw_panic!`, leaves its message and location in the error region.
+/// With the `catch-unwind` feature, the generated exports catch panics,
+/// returning `0` for pointers and `PANIC` for status codes.
+///
+/// Options are given as `name = size` pairs in bytes, in any order:
+/// - `input` / `output`: Reserve 16-byte aligned static channel buffers in the
+///   data segment instead of allocating them on the heap when the host requests
+///   them. Hosts may then request any size up to the reserved capacity. Both
+///   must be given together.
+/// - `error` / `log`: Size the error and log regions, which default to 256 and
+///   1024 bytes.
 ///
 /// Usage:
 /// ```rust
 /// zaw::setup_interop!();
 /// ```
+///
+/// With statically reserved channels:
+/// ```rust
+/// zaw::setup_interop!(input = 64 * 1024, output = 256 * 1024);
+/// ```
+///
+/// With a larger error region:
+/// ```rust
+/// zaw::setup_interop!(error = 1024);
+/// # assert_eq!(1024, getErrorSize());
+/// ```
 #[macro_export]
 macro_rules! setup_interop {
-    () => {
+    (@munch [$($input:expr)?] [$($output:expr)?] [$($error:expr)?] [$($log:expr)?] input = $value:expr, $($rest:tt)*) => {
+        $crate::setup_interop!(@munch [$value] [$($output)?] [$($error)?] [$($log)?] $($rest)*);
+    };
+    (@munch [$($input:expr)?] [$($output:expr)?] [$($error:expr)?] [$($log:expr)?] output = $value:expr, $($rest:tt)*) => {
+        $crate::setup_interop!(@munch [$($input)?] [$value] [$($error)?] [$($log)?] $($rest)*);
+    };
+    (@munch [$($input:expr)?] [$($output:expr)?] [$($error:expr)?] [$($log:expr)?] error = $value:expr, $($rest:tt)*) => {
+        $crate::setup_interop!(@munch [$($input)?] [$($output)?] [$value] [$($log)?] $($rest)*);
+    };
+    (@munch [$($input:expr)?] [$($output:expr)?] [$($error:expr)?] [$($log:expr)?] log = $value:expr, $($rest:tt)*) => {
+        $crate::setup_interop!(@munch [$($input)?] [$($output)?] [$($error)?] [$value] $($rest)*);
+    };
+    (@munch $input:tt $output:tt $error:tt $log:tt $name:ident = $value:expr, $($rest:tt)*) => {
+        compile_error!(concat!("Unknown setup_interop! option `", stringify!($name), "`"));
+    };
+    (@munch $input:tt $output:tt $error:tt $log:tt) => {
+        $crate::setup_interop!(@channels $input $output);
+        $crate::setup_interop!(@error $error);
+        $crate::setup_interop!(@log $log);
+        $crate::setup_interop!(@common);
+
+        // Every generated export calls this first, so the configured regions
+        // and the panic hook are in place before anything can be written to them
+        fn zaw_init() {
+            zaw_error_region();
+            zaw_log_region();
+            $crate::interop::panic::install_panic_hook();
+        }
+    };
+    (@channels [] []) => {
+        #[no_mangle]
+        pub extern "C" fn allocateInputChannel(size: i32) -> i32 {
+            zaw_init();
+            $crate::interop::panic::catch_panic(0, || $crate::interop::allocate_input_channel(size))
+        }
+
+        #[no_mangle]
+        pub extern "C" fn allocateOutputChannel(size: i32) -> i32 {
+            zaw_init();
+            $crate::interop::panic::catch_panic(0, || $crate::interop::allocate_output_channel(size))
+        }
+
+        #[no_mangle]
+        pub extern "C" fn reallocateInputChannel(size: i32) -> i32 {
+            zaw_init();
+            $crate::interop::panic::catch_panic(0, || $crate::interop::reallocate_input_channel(size))
+        }
+
+        #[no_mangle]
+        pub extern "C" fn reallocateOutputChannel(size: i32) -> i32 {
+            zaw_init();
+            $crate::interop::panic::catch_panic(0, || $crate::interop::reallocate_output_channel(size))
+        }
+    };
+    (@channels [$input:expr] [$output:expr]) => {
+        static ZAW_INPUT_STORAGE: $crate::interop::StaticChannel<
+            { $crate::interop::static_channel_words($input) },
+        > = $crate::interop::StaticChannel::new();
+
+        static ZAW_OUTPUT_STORAGE: $crate::interop::StaticChannel<
+            { $crate::interop::static_channel_words($output) },
+        > = $crate::interop::StaticChannel::new();
+
+        #[no_mangle]
+        pub extern "C" fn allocateInputChannel(size: i32) -> i32 {
+            zaw_init();
+            $crate::interop::panic::catch_panic(0, || $crate::interop::bind_static_input_channel(&ZAW_INPUT_STORAGE, size))
+        }
+
+        #[no_mangle]
+        pub extern "C" fn allocateOutputChannel(size: i32) -> i32 {
+            zaw_init();
+            $crate::interop::panic::catch_panic(0, || $crate::interop::bind_static_output_channel(&ZAW_OUTPUT_STORAGE, size))
+        }
+
+        // Static channels keep their contents and address, so resizing just rebinds them
+        #[no_mangle]
+        pub extern "C" fn reallocateInputChannel(size: i32) -> i32 {
+            zaw_init();
+            $crate::interop::panic::catch_panic(0, || $crate::interop::bind_static_input_channel(&ZAW_INPUT_STORAGE, size))
+        }
+
+        #[no_mangle]
+        pub extern "C" fn reallocateOutputChannel(size: i32) -> i32 {
+            zaw_init();
+            $crate::interop::panic::catch_panic(0, || $crate::interop::bind_static_output_channel(&ZAW_OUTPUT_STORAGE, size))
+        }
+    };
+    (@channels $input:tt $output:tt) => {
+        compile_error!("setup_interop! needs both `input` and `output` sizes, or neither");
+    };
+    (@error []) => {
+        fn zaw_error_region() {}
+    };
+    (@error [$size:expr]) => {
+        static ZAW_ERROR_REGION: $crate::interop::StaticRegion<{ $size }> =
+            $crate::interop::StaticRegion::new();
+
+        fn zaw_error_region() {
+            $crate::interop::error::set_error_region(&ZAW_ERROR_REGION);
+        }
+    };
+    (@log []) => {
+        fn zaw_log_region() {}
+    };
+    (@log [$size:expr]) => {
+        static ZAW_LOG_REGION: $crate::interop::StaticRegion<{ $size }> =
+            $crate::interop::StaticRegion::new();
+
+        fn zaw_log_region() {
+            $crate::interop::log::set_log_region(&ZAW_LOG_REGION);
+        }
+    };
+    (@common) => {
         #[no_mangle]
         pub extern "C" fn getErrorPtr() -> i32 {
+            zaw_init();
             $crate::interop::error::get_error_ptr()
         }
 
+        #[no_mangle]
+        pub extern "C" fn getErrorSize() -> i32 {
+            zaw_init();
+            $crate::interop::error::get_error_size()
+        }
+
+        #[no_mangle]
+        pub extern "C" fn getErrorCode() -> i32 {
+            zaw_init();
+            $crate::interop::error::get_error_code()
+        }
+
         #[no_mangle]
         pub extern "C" fn getLogPtr() -> i32 {
+            zaw_init();
             $crate::interop::log::get_log_ptr()
         }
 
         #[no_mangle]
-        pub extern "C" fn allocateInputChannel(size: i32) -> i32 {
-            $crate::interop::allocate_input_channel(size)
+        pub extern "C" fn getLogSize() -> i32 {
+            zaw_init();
+            $crate::interop::log::get_log_size()
         }
 
         #[no_mangle]
-        pub extern "C" fn allocateOutputChannel(size: i32) -> i32 {
-            $crate::interop::allocate_output_channel(size)
+        pub extern "C" fn setLogLevel(level: i32) -> i32 {
+            zaw_init();
+            $crate::interop::panic::catch_panic($crate::interop::error::PANIC, || {
+                $crate::interop::log::set_log_level(level)
+            })
         }
+
+        #[no_mangle]
+        pub extern "C" fn freeChannels() -> i32 {
+            zaw_init();
+            $crate::interop::panic::catch_panic($crate::interop::error::PANIC, $crate::interop::free_channels)
+        }
+
+        #[no_mangle]
+        pub extern "C" fn setInputLength(length: i32) -> i32 {
+            zaw_init();
+            $crate::interop::panic::catch_panic($crate::interop::error::PANIC, || {
+                $crate::interop::set_input_length(length)
+            })
+        }
+
+        #[no_mangle]
+        pub extern "C" fn getOutputLength() -> i32 {
+            zaw_init();
+            $crate::interop::get_output_length()
+        }
+    };
+    ($($name:ident = $value:expr),* $(,)?) => {
+        $crate::setup_interop!(@munch [] [] [] [] $($name = $value,)*);
     };
 }
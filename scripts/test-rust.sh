@@ -5,3 +5,14 @@ ROOT="$(dirname $(realpath $0))/..";
 
 cd $ROOT/implementations/wasm-rust
 cargo test
+cargo test --features serde
+cargo test --features type-tags
+cargo test --features journal
+cargo test --features trace
+cargo test --features catch-unwind
+cargo test --features log
+cargo test --all-features
+# Only the rlib, since a no_std cdylib needs the module's own panic handler
+cargo rustc --lib --crate-type rlib --no-default-features
+cargo rustc --lib --crate-type rlib --no-default-features --features alloc
+cargo rustc --lib --crate-type rlib --no-default-features --features panic-handler
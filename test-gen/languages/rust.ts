@@ -131,11 +131,7 @@ function generateTestCase(testCase: TestCase): string {
   code += '\n'
 
   // Verify buffer contents
-  code += indent([
-    `let expected = [${testCase.expectation.join(', ')}u8];`,
-    `let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, ${testCase.expectation.length}) };`,
-    'assert_eq!(&expected[..], actual);',
-  ])
+  code += indent([`assert_layout(&storage, &[${testCase.expectation.join(', ')}u8], &[${testCase.taggedExpectation.join(', ')}u8]);`])
 
   code += '\n'
 
@@ -159,8 +155,16 @@ function generateTestFile(testCases: TestCase[]): string {
     `use super::{Reader, Writer};
 
 #[cfg(test)]
+#[allow(clippy::approx_constant)]
 mod tests {
-    use super::*;`,
+    use super::*;
+
+    /// Checks the written bytes against the layout for this build, with or without type tags.
+    fn assert_layout(storage: &[u64], untagged: &[u8], tagged: &[u8]) {
+        let expected = if cfg!(feature = "type-tags") { tagged } else { untagged };
+        let actual = unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, expected.len()) };
+        assert_eq!(expected, actual);
+    }`,
   ]
 
   for (const testCase of testCases) {
@@ -11,6 +11,7 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [42, 0, 0, 0, 0, 0, 0, 0],
+    taggedExpectation: [0x01, 0x2a, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
   },
   {
     name: 'Multiple Uint8 writes',
@@ -32,6 +33,7 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [10, 20, 30, 0, 0, 0, 0, 0],
+    taggedExpectation: [0x01, 0x0a, 0x01, 0x14, 0x01, 0x1e, 0x00, 0x00],
   },
   {
     name: 'Uint32 write',
@@ -43,6 +45,7 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [0x78, 0x56, 0x34, 0x12, 0, 0, 0, 0],
+    taggedExpectation: [0x04, 0x00, 0x00, 0x00, 0x78, 0x56, 0x34, 0x12],
   },
   {
     name: 'Uint32 with alignment',
@@ -59,6 +62,7 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [10, 0, 0, 0, 0x78, 0x56, 0x34, 0x12],
+    taggedExpectation: [0x01, 0x0a, 0x04, 0x00, 0x78, 0x56, 0x34, 0x12],
   },
   {
     name: 'Int32 write',
@@ -70,6 +74,7 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [0, 0, 0, 0x80, 0, 0, 0, 0],
+    taggedExpectation: [0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80],
   },
   {
     name: 'Int32 with alignment',
@@ -86,6 +91,7 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [10, 0, 0, 0, 0xd6, 0xff, 0xff, 0xff],
+    taggedExpectation: [0x01, 0x0a, 0x05, 0x00, 0xd6, 0xff, 0xff, 0xff],
   },
   {
     name: 'Float32 write',
@@ -97,6 +103,7 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [0xd0, 0x0f, 0x49, 0x40, 0, 0, 0, 0],
+    taggedExpectation: [0x09, 0x00, 0x00, 0x00, 0xd0, 0x0f, 0x49, 0x40],
   },
   {
     name: 'Float32 with alignment',
@@ -113,6 +120,7 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [5, 0, 0, 0, 0xd0, 0x0f, 0x49, 0x40],
+    taggedExpectation: [0x01, 0x05, 0x09, 0x00, 0xd0, 0x0f, 0x49, 0x40],
   },
   {
     name: 'Float32 special values',
@@ -134,6 +142,10 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [0x00, 0x00, 0x80, 0x3f, 0x00, 0x00, 0x80, 0xbf, 0x00, 0x00, 0x00, 0x00],
+    taggedExpectation: [
+      0x09, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80, 0x3f, 0x09, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80, 0xbf, 0x09, 0x00, 0x00, 0x00, 0x00, 0x00,
+      0x00, 0x00,
+    ],
   },
   {
     name: 'Float64 write',
@@ -145,6 +157,7 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xef, 0x7f, 0, 0, 0, 0, 0, 0, 0, 0],
+    taggedExpectation: [0x0a, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xef, 0x7f],
   },
   {
     name: 'Float64 with alignment',
@@ -161,6 +174,7 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [5, 0, 0, 0, 0, 0, 0, 0, 0x6e, 0x86, 0x1b, 0xf0, 0xf9, 0x21, 0x09, 0x40],
+    taggedExpectation: [0x01, 0x05, 0x0a, 0x00, 0x00, 0x00, 0x00, 0x00, 0x6e, 0x86, 0x1b, 0xf0, 0xf9, 0x21, 0x09, 0x40],
   },
   {
     name: 'Multiple mixed primitives',
@@ -195,6 +209,10 @@ export const testCases: TestCase[] = [
       0xff, 0x00, 0x00, 0x00, 0x98, 0xba, 0xdc, 0xfe, 0xff, 0xff, 0xff, 0xff, 0xb6, 0xf3, 0x2d, 0x40, 0x58, 0x39, 0xb4, 0xc8, 0x76, 0xbe,
       0x05, 0xc0,
     ],
+    taggedExpectation: [
+      0x01, 0xff, 0x04, 0x00, 0x98, 0xba, 0xdc, 0xfe, 0x05, 0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff, 0x09, 0x00, 0x00, 0x00, 0xb6, 0xf3,
+      0x2d, 0x40, 0x0a, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x58, 0x39, 0xb4, 0xc8, 0x76, 0xbe, 0x05, 0xc0,
+    ],
   },
   {
     name: 'Empty Uint8 array',
@@ -206,6 +224,7 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [0, 0, 0, 0, 0, 0, 0, 0],
+    taggedExpectation: [0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x21, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
   },
   {
     name: 'Uint8 array',
@@ -217,6 +236,7 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [5, 0, 0, 0, 1, 2, 3, 4, 5, 0, 0, 0],
+    taggedExpectation: [0x04, 0x00, 0x00, 0x00, 0x05, 0x00, 0x00, 0x00, 0x21, 0x01, 0x02, 0x03, 0x04, 0x05, 0x00, 0x00],
   },
   {
     name: 'Uint32 array',
@@ -228,6 +248,10 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [2, 0, 0, 0, 0x78, 0x56, 0x34, 0x12, 0x21, 0x43, 0x65, 0x87],
+    taggedExpectation: [
+      0x04, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x24, 0x00, 0x00, 0x00, 0x78, 0x56, 0x34, 0x12, 0x21, 0x43, 0x65, 0x87, 0x00, 0x00,
+      0x00, 0x00,
+    ],
   },
   {
     name: 'Uint32 array with alignment',
@@ -244,6 +268,10 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [42, 0, 0, 0, 2, 0, 0, 0, 0x44, 0x33, 0x22, 0x11, 0x88, 0x77, 0x66, 0x55],
+    taggedExpectation: [
+      0x01, 0x2a, 0x04, 0x00, 0x02, 0x00, 0x00, 0x00, 0x24, 0x00, 0x00, 0x00, 0x44, 0x33, 0x22, 0x11, 0x88, 0x77, 0x66, 0x55, 0x00, 0x00,
+      0x00, 0x00,
+    ],
   },
   {
     name: 'Int32 array',
@@ -255,6 +283,10 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [3, 0, 0, 0, 0, 0, 0, 0x80, 0, 0, 0, 0, 0xff, 0xff, 0xff, 0x7f],
+    taggedExpectation: [
+      0x04, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x25, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80, 0x00, 0x00, 0x00, 0x00, 0xff, 0xff,
+      0xff, 0x7f,
+    ],
   },
   {
     name: 'Int32 array with alignment',
@@ -271,6 +303,10 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [255, 0, 0, 0, 3, 0, 0, 0, 0xff, 0xff, 0xff, 0xff, 0, 0, 0, 0, 1, 0, 0, 0],
+    taggedExpectation: [
+      0x01, 0xff, 0x04, 0x00, 0x03, 0x00, 0x00, 0x00, 0x25, 0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00,
+      0x00, 0x00,
+    ],
   },
   {
     name: 'Empty Float32 array',
@@ -282,6 +318,7 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [0, 0, 0, 0, 0, 0, 0, 0],
+    taggedExpectation: [0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x29, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
   },
   {
     name: 'Float32 array',
@@ -293,6 +330,10 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [3, 0, 0, 0, 0x00, 0x00, 0x80, 0x3f, 0x00, 0x00, 0x80, 0xbf, 0xd0, 0x0f, 0x49, 0x40],
+    taggedExpectation: [
+      0x04, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x29, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80, 0x3f, 0x00, 0x00, 0x80, 0xbf, 0xd0, 0x0f,
+      0x49, 0x40,
+    ],
   },
   {
     name: 'Float32 array with alignment',
@@ -309,6 +350,10 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [42, 0, 0, 0, 2, 0, 0, 0, 0xb6, 0xf3, 0x2d, 0x40, 0xb6, 0xf3, 0x2d, 0xc0],
+    taggedExpectation: [
+      0x01, 0x2a, 0x04, 0x00, 0x02, 0x00, 0x00, 0x00, 0x29, 0x00, 0x00, 0x00, 0xb6, 0xf3, 0x2d, 0x40, 0xb6, 0xf3, 0x2d, 0xc0, 0x00, 0x00,
+      0x00, 0x00,
+    ],
   },
   {
     name: 'Float64 array',
@@ -323,6 +368,10 @@ export const testCases: TestCase[] = [
       0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x6e, 0x86, 0x1b, 0xf0, 0xf9, 0x21,
       0x09, 0x40, 0x58, 0x39, 0xb4, 0xc8, 0x76, 0xbe, 0x05, 0xc0,
     ],
+    taggedExpectation: [
+      0x04, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x2a, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+      0x00, 0x00, 0x6e, 0x86, 0x1b, 0xf0, 0xf9, 0x21, 0x09, 0x40, 0x58, 0x39, 0xb4, 0xc8, 0x76, 0xbe, 0x05, 0xc0,
+    ],
   },
   {
     name: 'Float64 array with alignment',
@@ -342,6 +391,10 @@ export const testCases: TestCase[] = [
       0x2a, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xf0, 0x3f, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
       0xf0, 0xbf, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
     ],
+    taggedExpectation: [
+      0x01, 0x2a, 0x04, 0x00, 0x02, 0x00, 0x00, 0x00, 0x2a, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+      0xf0, 0x3f, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xf0, 0xbf,
+    ],
   },
   {
     name: 'Empty Uint8 elements',
@@ -353,6 +406,7 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [0, 0, 0, 0, 0, 0, 0, 0],
+    taggedExpectation: [0x21, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
   },
   {
     name: 'Uint8 elements',
@@ -364,6 +418,7 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [10, 20, 30, 40, 50, 0, 0, 0],
+    taggedExpectation: [0x21, 0x0a, 0x14, 0x1e, 0x28, 0x32, 0x00, 0x00],
   },
   {
     name: 'Uint32 elements',
@@ -375,6 +430,7 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [0x78, 0x56, 0x34, 0x12, 0x21, 0x43, 0x65, 0x87],
+    taggedExpectation: [0x24, 0x00, 0x00, 0x00, 0x78, 0x56, 0x34, 0x12, 0x21, 0x43, 0x65, 0x87, 0x00, 0x00, 0x00, 0x00],
   },
   {
     name: 'Uint32 elements with alignment',
@@ -391,6 +447,7 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [42, 0, 0, 0, 0x44, 0x33, 0x22, 0x11, 0x88, 0x77, 0x66, 0x55],
+    taggedExpectation: [0x01, 0x2a, 0x24, 0x00, 0x44, 0x33, 0x22, 0x11, 0x88, 0x77, 0x66, 0x55, 0x00, 0x00, 0x00, 0x00],
   },
   {
     name: 'Int32 elements',
@@ -402,6 +459,7 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [0, 0, 0, 0x80, 0, 0, 0, 0, 0xff, 0xff, 0xff, 0x7f],
+    taggedExpectation: [0x25, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80, 0x00, 0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0x7f],
   },
   {
     name: 'Int32 elements with alignment',
@@ -418,6 +476,7 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [255, 0, 0, 0, 0xff, 0xff, 0xff, 0xff, 0, 0, 0, 0, 1, 0, 0, 0],
+    taggedExpectation: [0x01, 0xff, 0x25, 0x00, 0xff, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00],
   },
   {
     name: 'Empty Float32 elements',
@@ -429,6 +488,7 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [0, 0, 0, 0, 0, 0, 0, 0],
+    taggedExpectation: [0x29, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
   },
   {
     name: 'Float32 elements',
@@ -440,6 +500,7 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80, 0x3f, 0x00, 0x00, 0x80, 0xbf],
+    taggedExpectation: [0x29, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80, 0x3f, 0x00, 0x00, 0x80, 0xbf],
   },
   {
     name: 'Float32 elements with alignment',
@@ -456,6 +517,7 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [42, 0, 0, 0, 0xd0, 0x0f, 0x49, 0x40, 0xb6, 0xf3, 0x2d, 0x40],
+    taggedExpectation: [0x01, 0x2a, 0x29, 0x00, 0xd0, 0x0f, 0x49, 0x40, 0xb6, 0xf3, 0x2d, 0x40, 0x00, 0x00, 0x00, 0x00],
   },
   {
     name: 'Float64 elements',
@@ -467,6 +529,10 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [0, 0, 0, 0, 0, 0, 0, 0, 0x6e, 0x86, 0x1b, 0xf0, 0xf9, 0x21, 0x09, 0x40],
+    taggedExpectation: [
+      0x2a, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x6e, 0x86, 0x1b, 0xf0, 0xf9, 0x21,
+      0x09, 0x40,
+    ],
   },
   {
     name: 'Float64 elements with alignment',
@@ -483,6 +549,10 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [42, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xf0, 0x3f, 0, 0, 0, 0, 0, 0, 0xf0, 0xbf],
+    taggedExpectation: [
+      0x01, 0x2a, 0x2a, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xf0, 0x3f, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+      0xf0, 0xbf,
+    ],
   },
   {
     name: 'Tuple of Uint32',
@@ -494,6 +564,7 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [10, 0, 0, 0, 20, 0, 0, 0, 30, 0, 0, 0],
+    taggedExpectation: [0x24, 0x00, 0x00, 0x00, 0x0a, 0x00, 0x00, 0x00, 0x14, 0x00, 0x00, 0x00, 0x1e, 0x00, 0x00, 0x00],
   },
   {
     name: 'Complex mixed types with arrays and elementss',
@@ -529,6 +600,11 @@ export const testCases: TestCase[] = [
       0x00, 0x00, 0xb6, 0xf3, 0x9d, 0x3f, 0x2d, 0xb2, 0xb5, 0x40, 0x8d, 0x97, 0x6e, 0x12, 0x83, 0xc0, 0x23, 0x40, 0x00, 0x00, 0x00, 0x00,
       0x00, 0x00, 0x00, 0x00,
     ],
+    taggedExpectation: [
+      0x01, 0xff, 0x04, 0x00, 0x01, 0xef, 0xcd, 0xab, 0x04, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x25, 0x00, 0x00, 0x00, 0x9c, 0xff,
+      0xff, 0xff, 0x00, 0x00, 0x00, 0x00, 0x64, 0x00, 0x00, 0x00, 0x29, 0x00, 0x00, 0x00, 0xb6, 0xf3, 0x9d, 0x3f, 0x2d, 0xb2, 0xb5, 0x40,
+      0x2a, 0x00, 0x00, 0x00, 0x8d, 0x97, 0x6e, 0x12, 0x83, 0xc0, 0x23, 0x40,
+    ],
   },
   {
     name: 'Write sequence with all types',
@@ -618,6 +694,18 @@ export const testCases: TestCase[] = [
       0xff, 0xff, 0x00, 0x00, 0x00, 0x00, 0x64, 0x00, 0x00, 0x00, 0xb6, 0xf3, 0x2d, 0x40, 0xb6, 0xf3, 0x2d, 0xc0, 0x18, 0x2d, 0x44, 0x54,
       0xfb, 0x21, 0x09, 0x40, 0x18, 0x2d, 0x44, 0x54, 0xfb, 0x21, 0x09, 0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
     ],
+    taggedExpectation: [
+      0x01, 0x2a, 0x04, 0x00, 0x78, 0x56, 0x34, 0x12, 0x05, 0x00, 0x00, 0x00, 0xd6, 0xff, 0xff, 0xff, 0x09, 0x00, 0x00, 0x00, 0xd0, 0x0f,
+      0x49, 0x40, 0x0a, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x90, 0xf7, 0xaa, 0x95, 0x09, 0xbf, 0x05, 0x40, 0x04, 0x00, 0x00, 0x00,
+      0x03, 0x00, 0x00, 0x00, 0x21, 0x01, 0x02, 0x03, 0x04, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x24, 0x00, 0x00, 0x00, 0xdd, 0xcc,
+      0xbb, 0xaa, 0x11, 0x00, 0xff, 0xee, 0x04, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x25, 0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff,
+      0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x29, 0x00, 0x00, 0x00, 0x00, 0x00,
+      0x80, 0x3f, 0x00, 0x00, 0x80, 0xbf, 0x04, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x2a, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+      0x00, 0x00, 0xf0, 0x3f, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xf0, 0xbf, 0x21, 0x0a, 0x14, 0x1e, 0x24, 0x00, 0x00, 0x00, 0x44, 0x33,
+      0x22, 0x11, 0x88, 0x77, 0x66, 0x55, 0x25, 0x00, 0x00, 0x00, 0x9c, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x00, 0x64, 0x00, 0x00, 0x00,
+      0x29, 0x00, 0x00, 0x00, 0xb6, 0xf3, 0x2d, 0x40, 0xb6, 0xf3, 0x2d, 0xc0, 0x2a, 0x00, 0x00, 0x00, 0x18, 0x2d, 0x44, 0x54, 0xfb, 0x21,
+      0x09, 0x40, 0x18, 0x2d, 0x44, 0x54, 0xfb, 0x21, 0x09, 0xc0,
+    ],
   },
   {
     name: 'Complex mixed types',
@@ -647,6 +735,11 @@ export const testCases: TestCase[] = [
       99, 0, 0, 0, 42, 0, 0, 0, 0x00, 0x00, 0xc0, 0x3f, 2, 0, 0, 0, 0x9a, 0x99, 0x99, 0x99, 0x99, 0x99, 0xf1, 0x3f, 0x9a, 0x99, 0x99, 0x99,
       0x99, 0x99, 0x01, 0x40,
     ],
+    taggedExpectation: [
+      0x01, 0x63, 0x04, 0x00, 0x2a, 0x00, 0x00, 0x00, 0x09, 0x00, 0x00, 0x00, 0x00, 0x00, 0xc0, 0x3f, 0x04, 0x00, 0x00, 0x00, 0x02, 0x00,
+      0x00, 0x00, 0x2a, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x9a, 0x99, 0x99, 0x99, 0x99, 0x99, 0xf1, 0x3f, 0x9a, 0x99, 0x99, 0x99,
+      0x99, 0x99, 0x01, 0x40,
+    ],
   },
   {
     name: 'Back-to-back alignment requirements',
@@ -691,6 +784,10 @@ export const testCases: TestCase[] = [
       1, 0, 0, 0, 2, 0, 0, 0, 3, 0, 0, 0, 0x00, 0x00, 0x80, 0x40, 5, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x18, 0x40, 7, 0, 0, 0, 0, 0, 0,
       0,
     ],
+    taggedExpectation: [
+      0x01, 0x01, 0x04, 0x00, 0x02, 0x00, 0x00, 0x00, 0x01, 0x03, 0x09, 0x00, 0x00, 0x00, 0x80, 0x40, 0x01, 0x05, 0x0a, 0x00, 0x00, 0x00,
+      0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x18, 0x40, 0x01, 0x07, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ],
   },
   {
     name: 'Empty arrays of different types',
@@ -722,6 +819,11 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+    taggedExpectation: [
+      0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x21, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x24, 0x00, 0x00, 0x00, 0x04, 0x00,
+      0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x25, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x29, 0x00, 0x00, 0x00,
+      0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x2a, 0x00, 0x00, 0x00,
+    ],
   },
   {
     name: 'Mixed operations with reset',
@@ -748,6 +850,10 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [10, 0, 0, 0, 0x78, 0x56, 0x34, 0x12, 0xa4, 0x70, 0x9d, 0x3f, 20, 0, 0, 0],
+    taggedExpectation: [
+      0x01, 0x0a, 0x04, 0x00, 0x78, 0x56, 0x34, 0x12, 0x09, 0x00, 0x00, 0x00, 0xa4, 0x70, 0x9d, 0x3f, 0x01, 0x14, 0x00, 0x00, 0x00, 0x00,
+      0x00, 0x00,
+    ],
   },
   {
     name: 'All possible primitive values',
@@ -839,6 +945,15 @@ export const testCases: TestCase[] = [
       0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xef, 0x7f, 0x01, 0x00,
       0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
     ],
+    taggedExpectation: [
+      0x01, 0x00, 0x01, 0x7f, 0x01, 0xff, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0x7f, 0x04, 0x00,
+      0x00, 0x00, 0xff, 0xff, 0xff, 0xff, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+      0x05, 0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0x7f, 0x09, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x09, 0x00, 0x00, 0x00, 0xfd, 0xff,
+      0x7f, 0x00, 0x09, 0x00, 0x00, 0x00, 0xff, 0xff, 0x7f, 0x7f, 0x0a, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+      0x0a, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80, 0x0a, 0x00, 0x00, 0x00, 0x00, 0x00,
+      0x00, 0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xef, 0x7f, 0x0a, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00,
+      0x00, 0x00, 0x00, 0x00,
+    ],
   },
 
   {
@@ -851,6 +966,7 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [42, 0, 0, 0, 0, 0, 0, 0],
+    taggedExpectation: [0x01, 0x2a, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
   },
   {
     name: 'Multiple Uint8 inits',
@@ -872,6 +988,7 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [10, 20, 30, 0, 0, 0, 0, 0],
+    taggedExpectation: [0x01, 0x0a, 0x01, 0x14, 0x01, 0x1e, 0x00, 0x00],
   },
   {
     name: 'Uint32 init',
@@ -883,6 +1000,7 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [0x78, 0x56, 0x34, 0x12, 0, 0, 0, 0],
+    taggedExpectation: [0x04, 0x00, 0x00, 0x00, 0x78, 0x56, 0x34, 0x12],
   },
   {
     name: 'Uint32 init with alignment',
@@ -899,6 +1017,7 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [10, 0, 0, 0, 0x78, 0x56, 0x34, 0x12],
+    taggedExpectation: [0x01, 0x0a, 0x04, 0x00, 0x78, 0x56, 0x34, 0x12],
   },
   {
     name: 'Int32 init',
@@ -910,6 +1029,7 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [0, 0, 0, 0x80, 0, 0, 0, 0],
+    taggedExpectation: [0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80],
   },
   {
     name: 'Float32 init',
@@ -921,6 +1041,7 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [0xd0, 0x0f, 0x49, 0x40, 0, 0, 0, 0],
+    taggedExpectation: [0x09, 0x00, 0x00, 0x00, 0xd0, 0x0f, 0x49, 0x40],
   },
   {
     name: 'Float32 init with alignment',
@@ -937,6 +1058,7 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [5, 0, 0, 0, 0xd0, 0x0f, 0x49, 0x40],
+    taggedExpectation: [0x01, 0x05, 0x09, 0x00, 0xd0, 0x0f, 0x49, 0x40],
   },
   {
     name: 'Float64 init',
@@ -948,6 +1070,7 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [0x6e, 0x86, 0x1b, 0xf0, 0xf9, 0x21, 0x09, 0x40, 0, 0, 0, 0, 0, 0, 0, 0],
+    taggedExpectation: [0x0a, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x6e, 0x86, 0x1b, 0xf0, 0xf9, 0x21, 0x09, 0x40],
   },
   {
     name: 'Float64 init with alignment',
@@ -964,6 +1087,7 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [5, 0, 0, 0, 0, 0, 0, 0, 0x6e, 0x86, 0x1b, 0xf0, 0xf9, 0x21, 0x09, 0x40],
+    taggedExpectation: [0x01, 0x05, 0x0a, 0x00, 0x00, 0x00, 0x00, 0x00, 0x6e, 0x86, 0x1b, 0xf0, 0xf9, 0x21, 0x09, 0x40],
   },
   {
     name: 'Mixed primitive inits',
@@ -998,6 +1122,10 @@ export const testCases: TestCase[] = [
       0xff, 0x00, 0x00, 0x00, 0x98, 0xba, 0xdc, 0xfe, 0xff, 0xff, 0xff, 0xff, 0xb6, 0xf3, 0x2d, 0x40, 0x58, 0x39, 0xb4, 0xc8, 0x76, 0xbe,
       0x05, 0xc0,
     ],
+    taggedExpectation: [
+      0x01, 0xff, 0x04, 0x00, 0x98, 0xba, 0xdc, 0xfe, 0x05, 0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff, 0x09, 0x00, 0x00, 0x00, 0xb6, 0xf3,
+      0x2d, 0x40, 0x0a, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x58, 0x39, 0xb4, 0xc8, 0x76, 0xbe, 0x05, 0xc0,
+    ],
   },
   {
     name: 'Empty Uint8 initArray',
@@ -1009,6 +1137,7 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [0, 0, 0, 0, 0, 0, 0, 0],
+    taggedExpectation: [0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x21, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
   },
   {
     name: 'Uint8 initArray',
@@ -1020,6 +1149,7 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [5, 0, 0, 0, 1, 2, 3, 4, 5, 0, 0, 0],
+    taggedExpectation: [0x04, 0x00, 0x00, 0x00, 0x05, 0x00, 0x00, 0x00, 0x21, 0x01, 0x02, 0x03, 0x04, 0x05, 0x00, 0x00],
   },
   {
     name: 'Uint32 initArray',
@@ -1031,6 +1161,10 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [2, 0, 0, 0, 0x78, 0x56, 0x34, 0x12, 0x21, 0x43, 0x65, 0x87],
+    taggedExpectation: [
+      0x04, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x24, 0x00, 0x00, 0x00, 0x78, 0x56, 0x34, 0x12, 0x21, 0x43, 0x65, 0x87, 0x00, 0x00,
+      0x00, 0x00,
+    ],
   },
   {
     name: 'Uint32 initArray with alignment',
@@ -1047,6 +1181,10 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [42, 0, 0, 0, 2, 0, 0, 0, 0x44, 0x33, 0x22, 0x11, 0x88, 0x77, 0x66, 0x55],
+    taggedExpectation: [
+      0x01, 0x2a, 0x04, 0x00, 0x02, 0x00, 0x00, 0x00, 0x24, 0x00, 0x00, 0x00, 0x44, 0x33, 0x22, 0x11, 0x88, 0x77, 0x66, 0x55, 0x00, 0x00,
+      0x00, 0x00,
+    ],
   },
   {
     name: 'Int32 initArray',
@@ -1058,6 +1196,10 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [3, 0, 0, 0, 0, 0, 0, 0x80, 0, 0, 0, 0, 0xff, 0xff, 0xff, 0x7f],
+    taggedExpectation: [
+      0x04, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x25, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80, 0x00, 0x00, 0x00, 0x00, 0xff, 0xff,
+      0xff, 0x7f,
+    ],
   },
   {
     name: 'Float32 initArray',
@@ -1069,6 +1211,10 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xd0, 0x0f, 0x49, 0x40, 0xb6, 0xf3, 0x2d, 0xc0],
+    taggedExpectation: [
+      0x04, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x29, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xd0, 0x0f, 0x49, 0x40, 0xb6, 0xf3,
+      0x2d, 0xc0,
+    ],
   },
   {
     name: 'Float64 initArray',
@@ -1083,6 +1229,10 @@ export const testCases: TestCase[] = [
       0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x6e, 0x86, 0x1b, 0xf0, 0xf9, 0x21,
       0x09, 0x40, 0x58, 0x39, 0xb4, 0xc8, 0x76, 0xbe, 0x05, 0xc0,
     ],
+    taggedExpectation: [
+      0x04, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x2a, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+      0x00, 0x00, 0x6e, 0x86, 0x1b, 0xf0, 0xf9, 0x21, 0x09, 0x40, 0x58, 0x39, 0xb4, 0xc8, 0x76, 0xbe, 0x05, 0xc0,
+    ],
   },
   {
     name: 'Empty Uint8 initElements',
@@ -1094,6 +1244,7 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [0, 0, 0, 0, 0, 0, 0, 0],
+    taggedExpectation: [0x21, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
   },
   {
     name: 'Uint8 initElements',
@@ -1105,6 +1256,7 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [10, 20, 30, 40, 50, 0, 0, 0],
+    taggedExpectation: [0x21, 0x0a, 0x14, 0x1e, 0x28, 0x32, 0x00, 0x00],
   },
   {
     name: 'Uint32 initElements',
@@ -1116,6 +1268,7 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [0x78, 0x56, 0x34, 0x12, 0x21, 0x43, 0x65, 0x87],
+    taggedExpectation: [0x24, 0x00, 0x00, 0x00, 0x78, 0x56, 0x34, 0x12, 0x21, 0x43, 0x65, 0x87, 0x00, 0x00, 0x00, 0x00],
   },
   {
     name: 'Uint32 initElements with alignment',
@@ -1132,6 +1285,7 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [42, 0, 0, 0, 0x44, 0x33, 0x22, 0x11, 0x88, 0x77, 0x66, 0x55],
+    taggedExpectation: [0x01, 0x2a, 0x24, 0x00, 0x44, 0x33, 0x22, 0x11, 0x88, 0x77, 0x66, 0x55, 0x00, 0x00, 0x00, 0x00],
   },
   {
     name: 'Int32 initElements',
@@ -1143,6 +1297,7 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [0, 0, 0, 0x80, 0, 0, 0, 0, 0xff, 0xff, 0xff, 0x7f],
+    taggedExpectation: [0x25, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80, 0x00, 0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0x7f],
   },
   {
     name: 'Float32 initElements',
@@ -1154,6 +1309,7 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [0x00, 0x00, 0x00, 0x00, 0xd0, 0x0f, 0x49, 0x40],
+    taggedExpectation: [0x29, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xd0, 0x0f, 0x49, 0x40, 0x00, 0x00, 0x00, 0x00],
   },
   {
     name: 'Float64 initElements',
@@ -1165,6 +1321,10 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [0, 0, 0, 0, 0, 0, 0, 0, 0x6e, 0x86, 0x1b, 0xf0, 0xf9, 0x21, 0x09, 0x40],
+    taggedExpectation: [
+      0x2a, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x6e, 0x86, 0x1b, 0xf0, 0xf9, 0x21,
+      0x09, 0x40,
+    ],
   },
   {
     name: 'Mixed init with other operations',
@@ -1194,6 +1354,11 @@ export const testCases: TestCase[] = [
       99, 0, 0, 0, 42, 0, 0, 0, 0x00, 0x00, 0xc0, 0x3f, 2, 0, 0, 0, 0x9a, 0x99, 0x99, 0x99, 0x99, 0x99, 0xf1, 0x3f, 0x9a, 0x99, 0x99, 0x99,
       0x99, 0x99, 0x01, 0x40,
     ],
+    taggedExpectation: [
+      0x01, 0x63, 0x04, 0x00, 0x2a, 0x00, 0x00, 0x00, 0x09, 0x00, 0x00, 0x00, 0x00, 0x00, 0xc0, 0x3f, 0x04, 0x00, 0x00, 0x00, 0x02, 0x00,
+      0x00, 0x00, 0x2a, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x9a, 0x99, 0x99, 0x99, 0x99, 0x99, 0xf1, 0x3f, 0x9a, 0x99, 0x99, 0x99,
+      0x99, 0x99, 0x01, 0x40,
+    ],
   },
   {
     name: 'All init methods combined',
@@ -1229,6 +1394,11 @@ export const testCases: TestCase[] = [
       0x00, 0x00, 0x64, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x58, 0x39, 0xb4, 0xc8, 0x76, 0xbe, 0xf3, 0x3f, 0x83, 0xc0, 0xca, 0xa1,
       0x45, 0xb6, 0x16, 0x40, 0x00, 0x00, 0x00, 0x00,
     ],
+    taggedExpectation: [
+      0x01, 0x2a, 0x04, 0x00, 0x78, 0x56, 0x34, 0x12, 0x09, 0x00, 0x00, 0x00, 0xa4, 0x70, 0x9d, 0x3f, 0x04, 0x00, 0x00, 0x00, 0x03, 0x00,
+      0x00, 0x00, 0x25, 0x00, 0x00, 0x00, 0x9c, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x00, 0x64, 0x00, 0x00, 0x00, 0x2a, 0x00, 0x00, 0x00,
+      0x00, 0x00, 0x00, 0x00, 0x58, 0x39, 0xb4, 0xc8, 0x76, 0xbe, 0xf3, 0x3f, 0x83, 0xc0, 0xca, 0xa1, 0x45, 0xb6, 0x16, 0x40,
+    ],
   },
   {
     name: 'Float32 extreme values',
@@ -1250,5 +1420,9 @@ export const testCases: TestCase[] = [
       },
     ],
     expectation: [0xff, 0xff, 0x7f, 0x7f, 0xfd, 0xff, 0x7f, 0x00, 0xff, 0xff, 0x7f, 0xff],
+    taggedExpectation: [
+      0x09, 0x00, 0x00, 0x00, 0xff, 0xff, 0x7f, 0x7f, 0x09, 0x00, 0x00, 0x00, 0xfd, 0xff, 0x7f, 0x00, 0x09, 0x00, 0x00, 0x00, 0xff, 0xff,
+      0x7f, 0xff,
+    ],
   },
 ]
@@ -17,6 +17,8 @@ export type TestCase = {
   name: string
   operations: Operation[]
   expectation: number[]
+  // The layout when every value is prefixed with its type tag (protocol-conduit.md 7.7)
+  taggedExpectation: number[]
 }
 
 export type TestGenerator = {